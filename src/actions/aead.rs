@@ -0,0 +1,23 @@
+use std::fmt;
+
+// Shared by the decryption of all AEAD modes, a failed tag check is told apart from invalid arguments
+#[derive(Debug, PartialEq)]
+pub enum AeadError {
+    AuthenticationFailed,
+    InvalidArgument(String)
+}
+
+impl fmt::Display for AeadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AeadError::AuthenticationFailed => write!(f, "Authentication failed"),
+            AeadError::InvalidArgument(message) => write!(f, "{}", message)
+        }
+    }
+}
+
+impl From<String> for AeadError {
+    fn from(message: String) -> Self {
+        AeadError::InvalidArgument(message)
+    }
+}
@@ -1,20 +1,50 @@
 use openssl::symm::{Cipher, Crypter, Mode};
 
-pub fn execute(algorithm: &String, mode: &String, key: &Vec<u8>, input: Vec<u8>) -> Vec<u8> {
+pub fn execute(algorithm: &str, mode: &String, key: &Vec<u8>, input: Vec<u8>) -> Result<Vec<u8>, String> {
+
+    // The key has to fit the algorithm, an unknown algorithm must not fall back to AES-128
+    check_key(algorithm, key)?;
 
     let xor: Vec<u8> = vec![0xc0, 0xff, 0xee, 0xc0, 0xff, 0xee, 0xc0, 0xff, 0xee, 0xc0, 0xff, 0xee, 0xc0, 0xff, 0xee, 0x11];
 
     match mode.as_str() {
-        "encrypt" => encrypt(algorithm, &key, input, xor),
-        "decrypt" => decrypt(algorithm,&key, input, xor),
-        _ => input
+        "encrypt" => Ok(encrypt(algorithm, &key, input, xor)),
+        "decrypt" => Ok(decrypt(algorithm,&key, input, xor)),
+        _ => Ok(input)
+    }
+}
+
+// Key length in bytes of the supported algorithms, the SEA variants XOR a constant onto the AES output
+pub fn key_length(algorithm: &str) -> Option<usize> {
+    match algorithm {
+        "aes128" | "sea128" => Some(16),
+        "aes192" | "sea192" => Some(24),
+        "aes256" | "sea256" => Some(32),
+        _ => None
+    }
+}
+
+pub fn check_key(algorithm: &str, key: &[u8]) -> Result<(), String> {
+    match key_length(algorithm) {
+        Some(length) if length == key.len() => Ok(()),
+        Some(length) => Err(format!("Key of {} bytes does not match {}, which expects {} bytes", key.len(), algorithm, length)),
+        None => Err(format!("Unknown algorithm '{}'", algorithm))
+    }
+}
+
+// The AES variant is picked by the algorithm, check_key makes sure the key fits it
+fn cipher(algorithm: &str) -> Cipher {
+    match key_length(algorithm) {
+        Some(24) => Cipher::aes_192_ecb(),
+        Some(32) => Cipher::aes_256_ecb(),
+        _ => Cipher::aes_128_ecb()
     }
 }
 
-fn encrypt(algorithm: &String, key: &Vec<u8>, input: Vec<u8>, xor: Vec<u8>) -> Vec<u8> {
+fn encrypt(algorithm: &str, key: &Vec<u8>, input: Vec<u8>, xor: Vec<u8>) -> Vec<u8> {
 
-    // Prepare the Ciper and Crypter for encrypting with aes128
-    let cipher = Cipher::aes_128_ecb();
+    // Prepare the Ciper and Crypter for encrypting with the AES variant of the algorithm
+    let cipher = cipher(algorithm);
     let mut crypter = Crypter::new(cipher, Mode::Encrypt, &key, None).unwrap(); 
 
     crypter.pad(false);
@@ -24,8 +54,8 @@ fn encrypt(algorithm: &String, key: &Vec<u8>, input: Vec<u8>, xor: Vec<u8>) -> V
 
     ciphertext.truncate(count); // Truncate the buffer to the actual output size
 
-    // XOR the ciphertext and the fixed xor value byte by byte if algorithm is set to sea128
-    if algorithm == &"sea128".to_string() {
+    // XOR the ciphertext and the fixed xor value byte by byte if algorithm is a SEA variant
+    if algorithm.starts_with("sea") {
         for i in 0..ciphertext.len() {
             ciphertext[i] ^= xor[i];
         }
@@ -34,16 +64,16 @@ fn encrypt(algorithm: &String, key: &Vec<u8>, input: Vec<u8>, xor: Vec<u8>) -> V
     ciphertext
 }
 
-fn decrypt(algorithm: &String, key: &Vec<u8>, mut input: Vec<u8>, xor: Vec<u8>) -> Vec<u8> {
+fn decrypt(algorithm: &str, key: &Vec<u8>, mut input: Vec<u8>, xor: Vec<u8>) -> Vec<u8> {
 
-    // Prepare the Ciper and Crypter for decrypting aes128
-    let cipher = Cipher::aes_128_ecb();
+    // Prepare the Ciper and Crypter for decrypting with the AES variant of the algorithm
+    let cipher = cipher(algorithm);
     let mut crypter = Crypter::new(cipher, Mode::Decrypt, &key, None).unwrap();
 
     crypter.pad(false);
 
-    // XOR the ciphertext and the fixed xor value byte by byte if algorith is set to sea128
-    if algorithm == &"sea128".to_string() {
+    // XOR the ciphertext and the fixed xor value byte by byte if algorith is a SEA variant
+    if algorithm.starts_with("sea") {
         for i in 0..input.len() {
             input[i] ^= xor[i];
         }
@@ -68,7 +98,7 @@ mod tests {
 
         let expected: Vec<u8> = vec![0x0f, 0x91, 0x43, 0xa3, 0x78, 0x95, 0x06, 0x80, 0x4d, 0xf6, 0x05, 0x62, 0xf7, 0xf3, 0x12, 0x29];
 
-        let result = execute(&"sea128".to_string(),&"encrypt".to_string(), &key, input);
+        let result = execute(&"sea128".to_string(),&"encrypt".to_string(), &key, input).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -79,7 +109,7 @@ mod tests {
 
         let expected: Vec<u8> = vec![0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad, 0xde, 0xca, 0xf8, 0x88, 0x88, 0x33, 0x44, 0x55];
 
-        let result = execute(&"sea128".to_string(),&"decrypt".to_string(), &key, input);
+        let result = execute(&"sea128".to_string(),&"decrypt".to_string(), &key, input).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -90,7 +120,7 @@ mod tests {
 
         let expected: Vec<u8> = vec![0xcf, 0x6e, 0xad, 0x63, 0x87, 0x7b, 0xc6, 0x7f, 0xa3, 0x36, 0xfa, 0x8c, 0x37, 0x0c, 0xfc, 0x38];
 
-        let result = execute(&"aes128".to_string(),&"encrypt".to_string(), &key, input);
+        let result = execute(&"aes128".to_string(),&"encrypt".to_string(), &key, input).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -101,7 +131,50 @@ mod tests {
 
         let expected: Vec<u8> = vec![0xc4, 0x7d, 0x56, 0x06, 0x2b, 0x68, 0xa2, 0x41, 0x7b, 0xdf, 0x86, 0xc0, 0x43, 0xdc, 0x14, 0xd1];
 
-        let result = execute(&"aes128".to_string(),&"decrypt".to_string(), &key, input);
+        let result = execute(&"aes128".to_string(),&"decrypt".to_string(), &key, input).unwrap();
         assert_eq!(result, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn aes192_and_aes256_fips197() {
+        // FIPS-197 appendix C.2 and C.3
+        let input: Vec<u8> = (0..16).map(|i| i * 0x11).collect();
+        let key192: Vec<u8> = (0..24).collect();
+        let key256: Vec<u8> = (0..32).collect();
+
+        let expected192: Vec<u8> = vec![0xdd, 0xa9, 0x7c, 0xa4, 0x86, 0x4c, 0xdf, 0xe0, 0x6e, 0xaf, 0x70, 0xa0, 0xec, 0x0d, 0x71, 0x91];
+        let expected256: Vec<u8> = vec![0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf, 0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49, 0x60, 0x89];
+
+        assert_eq!(execute(&"aes192".to_string(), &"encrypt".to_string(), &key192, input.clone()).unwrap(), expected192);
+        assert_eq!(execute(&"aes256".to_string(), &"encrypt".to_string(), &key256, input.clone()).unwrap(), expected256);
+        assert_eq!(execute(&"aes256".to_string(), &"decrypt".to_string(), &key256, expected256).unwrap(), input);
+    }
+
+    #[test]
+    fn sea256_roundtrip() {
+        let key: Vec<u8> = (0..32).collect();
+        let input: Vec<u8> = (0..16).collect();
+
+        let ciphertext = execute(&"sea256".to_string(), &"encrypt".to_string(), &key, input.clone()).unwrap();
+        let aes = execute(&"aes256".to_string(), &"encrypt".to_string(), &key, input.clone()).unwrap();
+        assert_eq!(ciphertext[0], aes[0] ^ 0xc0);
+        assert_eq!(execute(&"sea256".to_string(), &"decrypt".to_string(), &key, ciphertext).unwrap(), input);
+    }
+
+    #[test]
+    fn key_length_mismatch() {
+        assert!(check_key("aes256", &[0; 32]).is_ok());
+        assert!(check_key("sea192", &[0; 24]).is_ok());
+        assert!(check_key("aes256", &[0; 16]).is_err());
+        assert!(check_key("aes512", &[0; 64]).is_err());
+    }
+
+    #[test]
+    fn execute_checks_the_key() {
+        let input: Vec<u8> = (0..16).collect();
+
+        assert!(execute("aes512", &"encrypt".to_string(), &vec![0; 16], input.clone()).is_err());
+        assert!(execute("aes128", &"encrypt".to_string(), &vec![0; 20], input.clone()).is_err());
+        assert!(execute("aes256", &"decrypt".to_string(), &vec![0; 16], input).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use super::{gf_operations, gfpoly_operations};
+
+pub fn execute(sequence: Vec<Vec<u8>>) -> (Vec<Vec<u8>>, usize) {
+    let semantic = "gcm".to_string();
+
+    let mut one = vec![0u8; 16];
+    one[0] = 0x80;
+
+    let mut c: Vec<Vec<u8>> = vec![one.clone()]; // Current connection polynomial C(x)
+    let mut b: Vec<Vec<u8>> = vec![one.clone()]; // C(x) before the last length change
+    let mut last_discrepancy = one;
+    let mut l: usize = 0;
+    let mut m: usize = 1;
+
+    for n in 0..sequence.len() {
+        // Discrepancy between s_n and the value predicted by C(x)
+        let mut d = sequence[n].clone();
+        for i in 1..=l.min(c.len() - 1) {
+            let product = gf_operations::gfmul(&semantic, c[i].clone(), sequence[n - i].clone());
+            d = gf_operations::add_vec(&d, &product);
+        }
+
+        if d.iter().all(|&x| x == 0) {
+            m += 1;
+            continue;
+        }
+
+        // C(x) - d/b * x^m * B(x), in characteristic 2 minus is plus
+        let factor = gf_operations::gfdiv(d.clone(), last_discrepancy.clone());
+        let mut correction = vec![vec![0u8; 16]; m];
+        correction.extend(gfpoly_operations::mul(&b, &vec![factor]));
+        let new_c = gfpoly_operations::add(&c, &correction);
+
+        if 2 * l <= n {
+            // The recurrence has to get longer
+            b = c;
+            l = n + 1 - l;
+            last_discrepancy = d;
+            m = 1;
+        } else {
+            m += 1;
+        }
+        c = new_c;
+    }
+    (c, l)
+}
+
+pub fn execute_bits(bits: Vec<u8>) -> Result<(Vec<Vec<u8>>, usize), String> {
+    if let Some(bit) = bits.iter().find(|&&bit| bit > 1) {
+        return Err(format!("Bit sequence contains {}, only 0 and 1 are allowed", bit));
+    }
+
+    // Embed GF(2) into GF(2^128), 0 and 1 stay 0 and 1
+    let sequence = bits
+        .iter()
+        .map(|&bit| {
+            let mut block = vec![0u8; 16];
+            if bit == 1 {
+                block[0] = 0x80;
+            }
+            block
+        })
+        .collect();
+
+    Ok(execute(sequence))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn bits_lfsr() {
+        // s_n = s_{n-3} + s_{n-4}, connection polynomial 1 + x^3 + x^4
+        let mut bits: Vec<u8> = vec![1, 0, 0, 0];
+        for n in 4..20 {
+            bits.push(bits[n - 3] ^ bits[n - 4]);
+        }
+
+        let one = vec![0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let zero = vec![0u8; 16];
+        let expected = vec![one.clone(), zero.clone(), zero, one.clone(), one];
+
+        assert_eq!(execute_bits(bits), Ok((expected, 4)));
+    }
+
+    #[test]
+    fn bits_all_zero() {
+        assert_eq!(execute_bits(vec![0; 8]), Ok((vec![vec![0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]], 0)));
+        assert!(execute_bits(vec![0, 1, 2]).is_err());
+    }
+
+    #[test]
+    fn gf128_recurrence() {
+        let semantic = "gcm".to_string();
+        let mut rng = StdRng::seed_from_u64(36);
+        let mut random_block = || {
+            let mut block = vec![0u8; 16];
+            rng.fill(&mut block[..]);
+            block
+        };
+
+        // s_n = c1 * s_{n-1} + c2 * s_{n-2} + c3 * s_{n-3}
+        let mut one = vec![0u8; 16];
+        one[0] = 0x80;
+        let connection = vec![one, random_block(), random_block(), random_block()];
+        let mut sequence = vec![random_block(), random_block(), random_block()];
+        for n in 3..10 {
+            let mut next = vec![0u8; 16];
+            for i in 1..=3 {
+                let product = gf_operations::gfmul(&semantic, connection[i].clone(), sequence[n - i].clone());
+                next = gf_operations::add_vec(&next, &product);
+            }
+            sequence.push(next);
+        }
+
+        assert_eq!(execute(sequence), (connection, 3));
+    }
+}
@@ -0,0 +1,278 @@
+// A matrix over GF(2) is a list of rows, every row a byte string whose bits are the columns.
+// Column j is bit 0x80 >> (j % 8) of byte j / 8, which is the bit order of a block as it is
+// written, so a 128 x 128 matrix acts directly on 16 byte blocks.
+pub type BitMatrix = Vec<Vec<u8>>;
+
+pub fn get(row: &[u8], j: usize) -> bool {
+    row[j / 8] & (0x80 >> (j % 8)) != 0
+}
+
+fn flip(row: &mut [u8], j: usize) {
+    row[j / 8] ^= 0x80 >> (j % 8);
+}
+
+fn xor_into(target: &mut [u8], source: &[u8]) {
+    for (a, b) in target.iter_mut().zip(source) {
+        *a ^= b;
+    }
+}
+
+// Index of the coefficient of alpha that bit 'position' of a block holds, the map is its own inverse
+fn coefficient_index(semantic: &str, position: usize) -> usize {
+    match semantic {
+        "xex" => 8 * (position / 8) + 7 - position % 8,
+        _ => position
+    }
+}
+
+// Multiply an element in coefficient representation by alpha, reducing by x^128 + x^7 + x^2 + x + 1
+fn times_alpha(a: u128) -> u128 {
+    let carry = a >> 127;
+    (a << 1) ^ (carry * 0x87)
+}
+
+// Turn the images of the basis elements alpha^i (in coefficient representation) into the
+// matrix acting on blocks of the given semantic
+fn from_images(images: &[u128], semantic: &str) -> BitMatrix {
+    let mut matrix = vec![vec![0u8; 16]; 128];
+    for column in 0..128 {
+        let image = images[coefficient_index(semantic, column)];
+        for (row, entries) in matrix.iter_mut().enumerate() {
+            if (image >> coefficient_index(semantic, row)) & 1 == 1 {
+                flip(entries, column);
+            }
+        }
+    }
+    matrix
+}
+
+// Matrix of x -> c * x
+pub fn mul_constant(c: &[u8], semantic: &str) -> BitMatrix {
+    let mut c_value: u128 = 0;
+    for position in (0..128).filter(|&position| get(c, position)) {
+        c_value |= 1 << coefficient_index(semantic, position);
+    }
+
+    // Image of alpha^i is c * alpha^i, obtained by shifting c
+    let mut images = Vec::with_capacity(128);
+    let mut image = c_value;
+    for _ in 0..128 {
+        images.push(image);
+        image = times_alpha(image);
+    }
+    from_images(&images, semantic)
+}
+
+// Matrix of x -> x^(2^k), the k-th power of the Frobenius map
+pub fn frobenius(k: u32, semantic: &str) -> BitMatrix {
+    // alpha^i is squared to alpha^(2i)
+    let mut powers = Vec::with_capacity(256);
+    let mut power: u128 = 1;
+    for _ in 0..256 {
+        powers.push(power);
+        power = times_alpha(power);
+    }
+    let images: Vec<u128> = (0..128).map(|i| powers[2 * i]).collect();
+    let square = from_images(&images, semantic);
+
+    // The Frobenius map has order 128
+    let mut result = identity(128);
+    for _ in 0..k % 128 {
+        result = mul_matrix(&square, &result);
+    }
+    result
+}
+
+pub fn identity(n: usize) -> BitMatrix {
+    let mut matrix = vec![vec![0u8; n.div_ceil(8)]; n];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        flip(row, i);
+    }
+    matrix
+}
+
+// Row i of A * B is the sum of the rows of B selected by row i of A
+pub fn mul_matrix(a: &[Vec<u8>], b: &[Vec<u8>]) -> BitMatrix {
+    let width = b.first().map_or(0, |row| row.len());
+    a.iter()
+        .map(|a_row| {
+            let mut row = vec![0u8; width];
+            for (j, b_row) in b.iter().enumerate() {
+                if get(a_row, j) {
+                    xor_into(&mut row, b_row);
+                }
+            }
+            row
+        })
+        .collect()
+}
+
+pub fn mul_vector(matrix: &[Vec<u8>], x: &[u8]) -> Vec<u8> {
+    let mut y = vec![0u8; matrix.len().div_ceil(8)];
+    for (i, row) in matrix.iter().enumerate() {
+        let parity = row.iter().zip(x).fold(0u32, |sum, (a, b)| sum + (a & b).count_ones());
+        if parity % 2 == 1 {
+            flip(&mut y, i);
+        }
+    }
+    y
+}
+
+// Every row needs the same length and at least 'columns' bits, rref and kernel index them unchecked
+pub fn check_columns(matrix: &[Vec<u8>], columns: usize) -> Result<(), String> {
+    let Some(first) = matrix.first() else {
+        return Ok(());
+    };
+    if matrix.iter().any(|row| row.len() != first.len()) {
+        return Err("All rows of the matrix need the same length".to_string());
+    }
+    if columns > first.len() * 8 {
+        return Err(format!("Matrix has only {} columns, not {}", first.len() * 8, columns));
+    }
+    Ok(())
+}
+
+// Reduced row echelon form of the first 'columns' columns, returns the pivot columns
+pub fn rref(matrix: &[Vec<u8>], columns: usize) -> (BitMatrix, Vec<usize>) {
+    let mut matrix = matrix.to_vec();
+    let rows = matrix.len();
+    let mut pivots = Vec::new();
+
+    for column in 0..columns {
+        let row = pivots.len();
+        if row == rows {
+            break;
+        }
+        let Some(pivot) = (row..rows).find(|&i| get(&matrix[i], column)) else {
+            continue;
+        };
+        matrix.swap(row, pivot);
+
+        let pivot_row = matrix[row].clone();
+        for (i, current) in matrix.iter_mut().enumerate() {
+            if i != row && get(current, column) {
+                xor_into(current, &pivot_row);
+            }
+        }
+        pivots.push(column);
+    }
+    (matrix, pivots)
+}
+
+pub fn rank(matrix: &[Vec<u8>], columns: usize) -> usize {
+    rref(matrix, columns).1.len()
+}
+
+// Basis of {x : A * x = 0}, one vector per free column
+pub fn kernel(matrix: &[Vec<u8>], columns: usize) -> BitMatrix {
+    let (reduced, pivots) = rref(matrix, columns);
+
+    let mut basis = Vec::new();
+    for free in (0..columns).filter(|column| !pivots.contains(column)) {
+        let mut vector = vec![0u8; columns.div_ceil(8)];
+        flip(&mut vector, free);
+        for (row, &pivot) in pivots.iter().enumerate() {
+            if get(&reduced[row], free) {
+                flip(&mut vector, pivot);
+            }
+        }
+        basis.push(vector);
+    }
+    basis
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::gf_operations;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn random_block(rng: &mut StdRng) -> Vec<u8> {
+        let mut block = vec![0u8; 16];
+        rng.fill(&mut block[..]);
+        block
+    }
+
+    #[test]
+    fn mul_constant_matches_gfmul() {
+        let mut rng = StdRng::seed_from_u64(39);
+        for semantic in ["gcm", "xex"] {
+            let c = random_block(&mut rng);
+            let matrix = mul_constant(&c, semantic);
+
+            for _ in 0..16 {
+                let x = random_block(&mut rng);
+                let expected = gf_operations::gfmul(&semantic.to_string(), c.clone(), x.clone());
+                assert_eq!(mul_vector(&matrix, &x), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn frobenius_matches_gfmul() {
+        let mut rng = StdRng::seed_from_u64(139);
+        for semantic in ["gcm", "xex"] {
+            for k in [1, 5, 64] {
+                let matrix = frobenius(k, semantic);
+
+                let x = random_block(&mut rng);
+                let mut expected = x.clone();
+                for _ in 0..k {
+                    expected = gf_operations::gfmul(&semantic.to_string(), expected.clone(), expected);
+                }
+                assert_eq!(mul_vector(&matrix, &x), expected);
+            }
+            assert_eq!(mul_matrix(&frobenius(127, semantic), &frobenius(1, semantic)), identity(128));
+        }
+    }
+
+    #[test]
+    fn composition_matches_gfmul() {
+        let mut rng = StdRng::seed_from_u64(239);
+        let c = random_block(&mut rng);
+        let x = random_block(&mut rng);
+
+        // x -> c * x^2
+        let matrix = mul_matrix(&mul_constant(&c, "gcm"), &frobenius(1, "gcm"));
+        let square = gf_operations::gfmul(&"gcm".to_string(), x.clone(), x.clone());
+        assert_eq!(mul_vector(&matrix, &x), gf_operations::gfmul(&"gcm".to_string(), c, square));
+    }
+
+    #[test]
+    fn kernel_of_artin_schreier_map() {
+        // x -> x^2 + x vanishes exactly on GF(2) = {0, 1}
+        for (semantic, one_byte) in [("gcm", 0x80), ("xex", 0x01)] {
+            let mut matrix = frobenius(1, semantic);
+            for (i, row) in matrix.iter_mut().enumerate() {
+                flip(row, i);
+            }
+
+            let mut one = vec![0u8; 16];
+            one[0] = one_byte;
+            assert_eq!(rank(&matrix, 128), 127);
+            assert_eq!(kernel(&matrix, 128), vec![one]);
+        }
+    }
+
+    #[test]
+    fn kernel_of_dependent_rows() {
+        let mut rng = StdRng::seed_from_u64(339);
+        let mut matrix: BitMatrix = (0..20).map(|_| vec![rng.gen::<u8>(), rng.gen::<u8>(), rng.gen::<u8>()]).collect();
+        let sum: Vec<u8> = matrix[0].iter().zip(&matrix[1]).map(|(a, b)| a ^ b).collect();
+        matrix.push(sum);
+
+        let basis = kernel(&matrix, 24);
+        assert_eq!(basis.len(), 24 - rank(&matrix, 24));
+        for vector in basis {
+            assert_eq!(mul_vector(&matrix, &vector), vec![0u8; 3]);
+        }
+    }
+
+    #[test]
+    fn check_columns_of_rows() {
+        assert!(check_columns(&[vec![0xc0], vec![0x60]], 8).is_ok());
+        assert!(check_columns(&[], 5).is_ok());
+        assert!(check_columns(&[vec![0xc0], vec![0x60]], 9).is_err());
+        assert!(check_columns(&[vec![0xc0], vec![0x60, 0x00]], 8).is_err());
+    }
+}
@@ -0,0 +1,289 @@
+use super::aes_sea_128;
+
+// ECB and CBC work on whole blocks and pad with PKCS#7 by default, the stream modes CFB, OFB and CTR need no padding
+pub fn default_padding(mode: &str) -> &'static str {
+    match mode {
+        "ecb" | "cbc" => "pkcs7",
+        _ => "none"
+    }
+}
+
+pub fn encrypt(algorithm: String, mode: String, padding: String, key: Vec<u8>, iv: Option<Vec<u8>>, plaintext: Vec<u8>) -> Result<Vec<u8>, String> {
+    let iv = check_arguments(&algorithm, &mode, &key, iv)?;
+    let plaintext = pad(&padding, plaintext)?;
+    let encrypt = &"encrypt".to_string();
+
+    let mut ciphertext = Vec::with_capacity(plaintext.len());
+    match mode.as_str() {
+        "ecb" | "cbc" => {
+            check_block_length(&plaintext, &padding)?;
+            let mut previous = iv;
+            for block in plaintext.chunks(16) {
+                let mut block = block.to_vec();
+                // CBC chains the previous ciphertext block (starting with the IV) into the next plaintext block
+                if mode == "cbc" {
+                    xor_into(&mut block, &previous);
+                }
+                let encrypted = aes_sea_128::execute(&algorithm, encrypt, &key, block)?;
+                ciphertext.extend_from_slice(&encrypted);
+                previous = encrypted;
+            }
+        }
+        "cfb" => {
+            let mut previous = iv;
+            for block in plaintext.chunks(16) {
+                let mut block = block.to_vec();
+                xor_into(&mut block, &aes_sea_128::execute(&algorithm, encrypt, &key, previous)?);
+                ciphertext.extend_from_slice(&block);
+                previous = block;
+            }
+        }
+        _ => ciphertext = keystream_mode(&algorithm, &mode, &key, iv, &plaintext)?
+    }
+    Ok(ciphertext)
+}
+
+pub fn decrypt(algorithm: String, mode: String, padding: String, key: Vec<u8>, iv: Option<Vec<u8>>, ciphertext: Vec<u8>) -> Result<Vec<u8>, String> {
+    let iv = check_arguments(&algorithm, &mode, &key, iv)?;
+    check_padding(&padding)?;
+    let encrypt = &"encrypt".to_string();
+
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    match mode.as_str() {
+        "ecb" | "cbc" => {
+            if !ciphertext.len().is_multiple_of(16) {
+                return Err(format!("Ciphertext of {} bytes is not a multiple of the block size", ciphertext.len()));
+            }
+            let mut previous = iv;
+            for block in ciphertext.chunks(16) {
+                let mut decrypted = aes_sea_128::execute(&algorithm, &"decrypt".to_string(), &key, block.to_vec())?;
+                if mode == "cbc" {
+                    xor_into(&mut decrypted, &previous);
+                }
+                plaintext.extend_from_slice(&decrypted);
+                previous = block.to_vec();
+            }
+        }
+        "cfb" => {
+            let mut previous = iv;
+            for block in ciphertext.chunks(16) {
+                let mut decrypted = block.to_vec();
+                xor_into(&mut decrypted, &aes_sea_128::execute(&algorithm, encrypt, &key, previous)?);
+                plaintext.extend_from_slice(&decrypted);
+                previous = block.to_vec();
+            }
+        }
+        _ => plaintext = keystream_mode(&algorithm, &mode, &key, iv, &ciphertext)?
+    }
+    unpad(&padding, plaintext)
+}
+
+// Returns the IV to start with, ECB takes none and uses a zero block internally
+fn check_arguments(algorithm: &str, mode: &str, key: &[u8], iv: Option<Vec<u8>>) -> Result<Vec<u8>, String> {
+    aes_sea_128::check_key(algorithm, key)?;
+    match (mode, iv) {
+        ("ecb", None) => Ok(vec![0u8; 16]),
+        ("ecb", Some(_)) => Err("ECB does not take an IV".to_string()),
+        ("cbc" | "cfb" | "ofb" | "ctr", Some(iv)) if iv.len() == 16 => Ok(iv),
+        ("cbc" | "cfb" | "ofb" | "ctr", Some(iv)) => Err(format!("IV has {} bytes, expected 16", iv.len())),
+        ("cbc" | "cfb" | "ofb" | "ctr", None) => Err(format!("Mode '{}' requires an IV", mode)),
+        _ => Err(format!("Unknown mode '{}'", mode))
+    }
+}
+
+fn check_padding(padding: &str) -> Result<(), String> {
+    match padding {
+        "pkcs7" | "iso7816" | "x923" | "zero" | "none" => Ok(()),
+        _ => Err(format!("Unknown padding '{}'", padding))
+    }
+}
+
+fn check_block_length(input: &[u8], padding: &str) -> Result<(), String> {
+    if !input.len().is_multiple_of(16) {
+        return Err(format!("Input of {} bytes is not a multiple of the block size, padding '{}' does not fill it", input.len(), padding));
+    }
+    Ok(())
+}
+
+fn xor_into(target: &mut [u8], source: &[u8]) {
+    for (a, b) in target.iter_mut().zip(source) {
+        *a ^= b;
+    }
+}
+
+// OFB encrypts the previous keystream block, CTR a big endian counter starting at the IV.
+// Both are their own inverse
+fn keystream_mode(algorithm: &str, mode: &str, key: &Vec<u8>, iv: Vec<u8>, input: &[u8]) -> Result<Vec<u8>, String> {
+    if mode != "ofb" {
+        return ctr(algorithm, key, &iv, input);
+    }
+    let encrypt = &"encrypt".to_string();
+
+    let mut output = Vec::with_capacity(input.len());
+    let mut keystream = iv;
+    for chunk in input.chunks(16) {
+        keystream = aes_sea_128::execute(algorithm, encrypt, key, keystream)?;
+        output.extend(chunk.iter().zip(&keystream).map(|(x, k)| x ^ k));
+    }
+    Ok(output)
+}
+
+// Counter mode over the whole 128 bit block, incremented as a big endian number
+pub fn ctr(algorithm: &str, key: &Vec<u8>, initial: &[u8], input: &[u8]) -> Result<Vec<u8>, String> {
+    let encrypt = &"encrypt".to_string();
+    let counter = u128::from_be_bytes(initial.try_into().unwrap());
+
+    let mut output = Vec::with_capacity(input.len());
+    for (i, chunk) in input.chunks(16).enumerate() {
+        let block = counter.wrapping_add(i as u128).to_be_bytes().to_vec();
+        let keystream = aes_sea_128::execute(algorithm, encrypt, key, block)?;
+        output.extend(chunk.iter().zip(&keystream).map(|(x, k)| x ^ k));
+    }
+    Ok(output)
+}
+
+// PKCS#7 appends n bytes of value n, ISO 7816-4 appends 0x80 and zeros, ANSI X9.23 appends zeros and n.
+// These always add at least one byte, zero padding only fills up an incomplete block
+fn pad(padding: &str, mut data: Vec<u8>) -> Result<Vec<u8>, String> {
+    check_padding(padding)?;
+    let n = 16 - data.len() % 16;
+    match padding {
+        "pkcs7" => data.extend(std::iter::repeat_n(n as u8, n)),
+        "iso7816" => {
+            data.push(0x80);
+            data.resize(data.len().next_multiple_of(16), 0);
+        }
+        "x923" => {
+            data.extend(std::iter::repeat_n(0, n - 1));
+            data.push(n as u8);
+        }
+        "zero" => data.resize(data.len().next_multiple_of(16), 0),
+        _ => {}
+    }
+    Ok(data)
+}
+
+fn unpad(padding: &str, mut data: Vec<u8>) -> Result<Vec<u8>, String> {
+    let invalid = || Err(format!("Invalid {} padding", padding));
+    match padding {
+        "pkcs7" | "x923" => {
+            let Some(&n) = data.last() else {
+                return invalid();
+            };
+            let n = n as usize;
+            if n == 0 || n > 16 || n > data.len() {
+                return invalid();
+            }
+            let filler = &data[data.len() - n..data.len() - 1];
+            let expected = if padding == "pkcs7" { n as u8 } else { 0 };
+            if filler.iter().any(|&byte| byte != expected) {
+                return invalid();
+            }
+            data.truncate(data.len() - n);
+        }
+        "iso7816" => {
+            let Some(position) = data.iter().rposition(|&byte| byte != 0) else {
+                return invalid();
+            };
+            if data[position] != 0x80 || data.len() - position > 16 {
+                return invalid();
+            }
+            data.truncate(position);
+        }
+        "zero" => {
+            let length = data.iter().rposition(|&byte| byte != 0).map_or(0, |position| position + 1);
+            data.truncate(length);
+        }
+        _ => {}
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::de_encode_base64::decode_str;
+
+    // SP 800-38A, appendix F: AES-128 key, IV and the four plaintext blocks
+    const KEY: &str = "K34VFiiu0qar9xWICc9PPA==";
+    const IV: &str = "AAECAwQFBgcICQoLDA0ODw==";
+    const COUNTER: &str = "8PHy8/T19vf4+fr7/P3+/w==";
+    const PLAINTEXT: &str = "a8G+4i5An5bpPX4Rc5MXKq4tilceA6ycnrdvrEWvjlEwyBxGo1zkEeX7wRkaClLv9p8kRd9PmxetK0F75mw3EA==";
+
+    #[test]
+    fn modes_sp800_38a() {
+        let cases = [
+            ("ecb", None, "Otd7tA16NmConsrzJGbvl/XT1YUDuWmd54WJWpb9uq9Dsc1/WY7OI4gbAOPtAwaIewx4XiforT+CIyBxBHJd1A=="),
+            ("cbc", Some(IV), "dkmrrIEZskbO6Y6bEukZfVCGy5tQchnuldsROpF2eLJzvta448F0O3EW5p4iIpUWP/HKoWgfrAkSDsowdYbhpw=="),
+            ("cfb", Some(IV), "Oz/ZLrctrSAzNEn46Dz7SsimRTegs6k/zePNrZ8c5YsmdR9no8uxQLGAjPGHpPTfwEsFNXxdHA7qxMZvn/fy5g=="),
+            ("ofb", Some(IV), "Oz/ZLrctrSAzNEn46Dz7SneJUI0WkY8D9TxS2sVO2CWXQAUenF/s9kNE96giYO3MMExlKPZZx3hmpRDZwdauXg=="),
+            ("ctr", Some(COUNTER), "h01hkbYg4yYb72hkmQ22zpgG9mt5cP3/hhcYe7n//f9a5N8+29XTXltPCQINsD6rHgMd2i++A9F5IXCg8wCc7g==")
+        ];
+
+        for (mode, iv, expected) in cases {
+            let iv = iv.map(decode_str);
+            let ciphertext = encrypt("aes128".to_string(), mode.to_string(), "none".to_string(), decode_str(KEY), iv.clone(), decode_str(PLAINTEXT)).unwrap();
+            assert_eq!(ciphertext, decode_str(expected), "{}", mode);
+
+            let plaintext = decrypt("aes128".to_string(), mode.to_string(), "none".to_string(), decode_str(KEY), iv, ciphertext).unwrap();
+            assert_eq!(plaintext, decode_str(PLAINTEXT), "{}", mode);
+        }
+    }
+
+    #[test]
+    fn cbc_pkcs7_matches_openssl() {
+        // Cross-checked with openssl enc -aes-128-cbc
+        let plaintext = decode_str("RGFzIGlzdCBlaW4gVGVzdA==");
+        let ciphertext = encrypt("aes128".to_string(), "cbc".to_string(), "pkcs7".to_string(), decode_str(KEY), Some(decode_str(IV)), plaintext.clone()).unwrap();
+        assert_eq!(ciphertext, decode_str("SFclBUBGbGfSPI+TaZTZOQHpghB7rJJ5YohydDztPhE="));
+    }
+
+    #[test]
+    fn paddings() {
+        let data = vec![0xaa; 13];
+        assert_eq!(pad("pkcs7", data.clone()).unwrap()[13..], [3, 3, 3]);
+        assert_eq!(pad("iso7816", data.clone()).unwrap()[13..], [0x80, 0, 0]);
+        assert_eq!(pad("x923", data.clone()).unwrap()[13..], [0, 0, 3]);
+        assert_eq!(pad("zero", data.clone()).unwrap()[13..], [0, 0, 0]);
+        assert_eq!(pad("pkcs7", vec![0xaa; 16]).unwrap().len(), 32);
+        assert_eq!(pad("zero", vec![0xaa; 16]).unwrap().len(), 16);
+
+        for padding in ["pkcs7", "iso7816", "x923", "zero"] {
+            assert_eq!(unpad(padding, pad(padding, data.clone()).unwrap()).unwrap(), data, "{}", padding);
+        }
+
+        assert!(unpad("pkcs7", vec![0xaa, 0xaa, 2, 3]).is_err());
+        assert!(unpad("pkcs7", vec![0xaa; 16]).is_err());
+        assert!(unpad("x923", vec![0xaa, 1, 2]).is_err());
+        assert!(unpad("iso7816", vec![0xaa, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn roundtrip_sea128() {
+        let key = decode_str("Xjq/GkpTSWoe3ZH0F+tjrQ==");
+        let plaintext = decode_str("RGFzIGlzdCBlaW4gVGVzdCBtaXQgbWVociBhbHMgMTYgQnl0ZXM=");
+
+        for mode in ["ecb", "cbc", "cfb", "ofb", "ctr"] {
+            let iv = (mode != "ecb").then(|| decode_str(IV));
+            for padding in ["pkcs7", "iso7816", "x923", "none"] {
+                if padding == "none" && default_padding(mode) != "none" {
+                    continue;
+                }
+                let ciphertext = encrypt("sea128".to_string(), mode.to_string(), padding.to_string(), key.clone(), iv.clone(), plaintext.clone()).unwrap();
+                let result = decrypt("sea128".to_string(), mode.to_string(), padding.to_string(), key.clone(), iv.clone(), ciphertext).unwrap();
+                assert_eq!(result, plaintext, "{} {}", mode, padding);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let key = decode_str(KEY);
+        assert!(encrypt("aes128".to_string(), "ecb".to_string(), "none".to_string(), key.clone(), None, vec![0u8; 15]).is_err());
+        assert!(encrypt("aes128".to_string(), "cbc".to_string(), "pkcs7".to_string(), key.clone(), None, Vec::new()).is_err());
+        assert!(encrypt("aes128".to_string(), "ecb".to_string(), "pkcs7".to_string(), key.clone(), Some(decode_str(IV)), Vec::new()).is_err());
+        assert!(encrypt("aes128".to_string(), "gcm".to_string(), "none".to_string(), key.clone(), Some(decode_str(IV)), Vec::new()).is_err());
+        assert!(encrypt("aes128".to_string(), "ctr".to_string(), "pkcs5".to_string(), key.clone(), Some(decode_str(IV)), Vec::new()).is_err());
+        assert!(decrypt("aes128".to_string(), "cbc".to_string(), "pkcs7".to_string(), key, Some(decode_str(IV)), vec![0u8; 17]).is_err());
+    }
+}
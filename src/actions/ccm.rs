@@ -0,0 +1,166 @@
+use super::aes_sea_128;
+use super::aead::AeadError;
+
+// Ciphertext and tag
+type CcmOutput = (Vec<u8>, Vec<u8>);
+
+// SP 800-38C: CBC-MAC over the formatted input, then counter mode. Nonce and payload length share
+// the 15 bytes after the flags, so a longer nonce leaves fewer bytes for the payload length
+pub fn encrypt(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, plaintext: Vec<u8>, ad: Vec<u8>, tag_length: usize) -> Result<CcmOutput, String> {
+    check_arguments(&algorithm, &nonce, &key, plaintext.len(), tag_length)?;
+
+    let mac = cbc_mac(&algorithm, &key, &format_input(&nonce, &plaintext, &ad, tag_length))?;
+    let (ciphertext, s0) = ctr(&algorithm, &key, &nonce, &plaintext)?;
+
+    let tag: Vec<u8> = mac.iter().zip(&s0).take(tag_length).map(|(t, s)| t ^ s).collect();
+    Ok((ciphertext, tag))
+}
+
+// The MAC is computed over the plaintext, so it is decrypted first and only returned if the tag matches
+pub fn decrypt(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, ciphertext: Vec<u8>, ad: Vec<u8>, tag: Vec<u8>, tag_length: usize) -> Result<Vec<u8>, AeadError> {
+    check_arguments(&algorithm, &nonce, &key, ciphertext.len(), tag_length)?;
+    if tag.len() != tag_length {
+        return Err(AeadError::InvalidArgument(format!("Tag has {} bytes, expected {}", tag.len(), tag_length)));
+    }
+
+    let (plaintext, s0) = ctr(&algorithm, &key, &nonce, &ciphertext)?;
+    let mac = cbc_mac(&algorithm, &key, &format_input(&nonce, &plaintext, &ad, tag_length))?;
+
+    let expected_tag: Vec<u8> = mac.iter().zip(&s0).take(tag_length).map(|(t, s)| t ^ s).collect();
+    if !openssl::memcmp::eq(&expected_tag, &tag) {
+        return Err(AeadError::AuthenticationFailed);
+    }
+    Ok(plaintext)
+}
+
+fn check_arguments(algorithm: &str, nonce: &[u8], key: &[u8], payload_length: usize, tag_length: usize) -> Result<(), String> {
+    aes_sea_128::check_key(algorithm, key)?;
+    if !(7..=13).contains(&nonce.len()) {
+        return Err(format!("Nonce has {} bytes, CCM expects 7 to 13", nonce.len()));
+    }
+    if !(4..=16).contains(&tag_length) || tag_length % 2 == 1 {
+        return Err(format!("Tag length of {} bytes is not one of 4, 6, 8, 10, 12, 14 or 16", tag_length));
+    }
+
+    let length_bytes = 15 - nonce.len();
+    if length_bytes < 8 && payload_length >> (8 * length_bytes) != 0 {
+        return Err(format!("Payload of {} bytes does not fit a {} byte nonce", payload_length, nonce.len()));
+    }
+    Ok(())
+}
+
+// B0 || encoded AD || padded payload
+fn format_input(nonce: &[u8], payload: &[u8], ad: &[u8], tag_length: usize) -> Vec<u8> {
+    let length_bytes = 15 - nonce.len();
+
+    // Flags: AD present, (t - 2) / 2 and q - 1
+    let flags = (u8::from(!ad.is_empty()) << 6) | (((tag_length as u8 - 2) / 2) << 3) | (length_bytes as u8 - 1);
+    let mut input = vec![flags];
+    input.extend_from_slice(nonce);
+    input.extend_from_slice(&(payload.len() as u64).to_be_bytes()[8 - length_bytes..]);
+
+    // The AD length is encoded in 2, 6 or 10 bytes depending on its size
+    if !ad.is_empty() {
+        let ad_length = ad.len() as u64;
+        if ad_length < 0xff00 {
+            input.extend_from_slice(&(ad_length as u16).to_be_bytes());
+        } else if ad_length <= u32::MAX as u64 {
+            input.extend_from_slice(&[0xff, 0xfe]);
+            input.extend_from_slice(&(ad_length as u32).to_be_bytes());
+        } else {
+            input.extend_from_slice(&[0xff, 0xff]);
+            input.extend_from_slice(&ad_length.to_be_bytes());
+        }
+        input.extend_from_slice(ad);
+        input.resize(input.len().next_multiple_of(16), 0);
+    }
+
+    input.extend_from_slice(payload);
+    input.resize(input.len().next_multiple_of(16), 0);
+    input
+}
+
+fn cbc_mac(algorithm: &str, key: &Vec<u8>, input: &[u8]) -> Result<Vec<u8>, String> {
+    let mode = &"encrypt".to_string();
+    let mut state = vec![0u8; 16];
+    for block in input.chunks(16) {
+        for (s, b) in state.iter_mut().zip(block) {
+            *s ^= b;
+        }
+        state = aes_sea_128::execute(algorithm, mode, key, state)?;
+    }
+    Ok(state)
+}
+
+// Counter block i is flags (q - 1) || nonce || i, block 0 masks the tag and the payload starts at block 1.
+// Returns the en- or decrypted payload and the encrypted block 0
+fn ctr(algorithm: &str, key: &Vec<u8>, nonce: &[u8], input: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let mode = &"encrypt".to_string();
+    let length_bytes = 15 - nonce.len();
+    let counter_block = |i: u64| -> Vec<u8> {
+        let mut block = vec![length_bytes as u8 - 1];
+        block.extend_from_slice(nonce);
+        block.extend_from_slice(&i.to_be_bytes()[8 - length_bytes..]);
+        block
+    };
+
+    let mut output = Vec::with_capacity(input.len());
+    for (i, chunk) in input.chunks(16).enumerate() {
+        let keystream = aes_sea_128::execute(algorithm, mode, key, counter_block(i as u64 + 1))?;
+        output.extend(chunk.iter().zip(&keystream).map(|(x, k)| x ^ k));
+    }
+
+    let s0 = aes_sea_128::execute(algorithm, mode, key, counter_block(0))?;
+    Ok((output, s0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::de_encode_base64::decode_str;
+
+    #[test]
+    fn encrypt_sp800_38c() {
+        // SP 800-38C, appendix C, examples 1 to 3
+        let key = decode_str("QEFCQ0RFRkdISUpLTE1OTw==");
+
+        let (ciphertext, tag) = encrypt("aes128".to_string(), decode_str("EBESExQVFg=="), key.clone(), decode_str("ICEiIw=="), decode_str("AAECAwQFBgc="), 4).unwrap();
+        assert_eq!((ciphertext, tag), (decode_str("cWIBWw=="), decode_str("TawlXQ==")));
+
+        let (ciphertext, tag) = encrypt("aes128".to_string(), decode_str("EBESExQVFhc="), key.clone(), decode_str("ICEiIyQlJicoKSorLC0uLw=="), decode_str("AAECAwQFBgcICQoLDA0ODw=="), 6).unwrap();
+        assert_eq!((ciphertext, tag), (decode_str("0qHw4FHqX2IIGneSBz1ZPQ=="), decode_str("H8ZPv6zN")));
+
+        let nonce = decode_str("EBESExQVFhcYGRob");
+        let plaintext = decode_str("ICEiIyQlJicoKSorLC0uLzAxMjM0NTY3");
+        let ad = decode_str("AAECAwQFBgcICQoLDA0ODxAREhM=");
+        let (ciphertext, tag) = encrypt("aes128".to_string(), nonce.clone(), key.clone(), plaintext.clone(), ad.clone(), 8).unwrap();
+        assert_eq!(ciphertext, decode_str("47IBqfW3GnqbHOrszZfnC2F2qtmkQoql"));
+        assert_eq!(tag, decode_str("SEOS+8GwmVE="));
+
+        assert_eq!(decrypt("aes128".to_string(), nonce, key, ciphertext, ad, tag, 8).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn decrypt_sea128_forged() {
+        let key = decode_str("Xjq/GkpTSWoe3ZH0F+tjrQ==");
+        let nonce = decode_str("4gF+BtR3ku/PUQ==");
+        let plaintext = decode_str("RGFzIGlzdCBlaW4gVGVzdCBtaXQgbWVociBhbHMgMTYgQnl0ZXM=");
+        let ad = decode_str("QUQtRGF0ZW4=");
+
+        let (ciphertext, tag) = encrypt("sea128".to_string(), nonce.clone(), key.clone(), plaintext.clone(), ad.clone(), 12).unwrap();
+        assert_eq!(decrypt("sea128".to_string(), nonce.clone(), key.clone(), ciphertext.clone(), ad.clone(), tag.clone(), 12).unwrap(), plaintext);
+
+        let mut forged = ciphertext;
+        forged[20] ^= 1;
+        assert_eq!(decrypt("sea128".to_string(), nonce, key, forged, ad, tag, 12), Err(AeadError::AuthenticationFailed));
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let key = vec![0u8; 16];
+        assert!(encrypt("aes128".to_string(), vec![0u8; 6], key.clone(), Vec::new(), Vec::new(), 8).is_err());
+        assert!(encrypt("aes128".to_string(), vec![0u8; 12], key.clone(), Vec::new(), Vec::new(), 5).is_err());
+        // A 13 byte nonce leaves two bytes for the payload length
+        assert!(encrypt("aes128".to_string(), vec![0u8; 13], key, vec![0u8; 0x10000], Vec::new(), 8).is_err());
+    }
+}
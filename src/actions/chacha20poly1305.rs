@@ -0,0 +1,201 @@
+use num::BigUint;
+use num::One;
+use super::aead::AeadError;
+
+// Ciphertext, tag, Poly1305 key, r and s
+type ChaChaOutput = (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>);
+
+// Authentic, plaintext, Poly1305 key, r and s
+type ChaChaDiagnostic = (bool, Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>);
+
+// "expand 32-byte k" as four little endian words
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+pub fn encrypt(nonce: Vec<u8>, key: Vec<u8>, plaintext: Vec<u8>, ad: Vec<u8>) -> Result<ChaChaOutput, String> {
+    check_arguments(&nonce, &key)?;
+
+    // Block 0 gives the one-time Poly1305 key, the keystream starts at block 1
+    let poly_key = chacha20_block(&key, 0, &nonce)[..32].to_vec();
+    let ciphertext = chacha20_xor(&key, 1, &nonce, &plaintext);
+    let tag = poly1305(&poly_key, &mac_data(&ad, &ciphertext));
+
+    let (r, s) = split_key(&poly_key);
+    Ok((ciphertext, tag, poly_key, r, s))
+}
+
+// The tag is checked in constant time before the plaintext is released
+pub fn decrypt(nonce: Vec<u8>, key: Vec<u8>, ciphertext: Vec<u8>, ad: Vec<u8>, tag: Vec<u8>) -> Result<Vec<u8>, AeadError> {
+    let (authentic, plaintext, ..) = decrypt_diagnostic(nonce, key, ciphertext, ad, tag)?;
+    if !authentic {
+        return Err(AeadError::AuthenticationFailed);
+    }
+    Ok(plaintext)
+}
+
+// Hands out the plaintext and the Poly1305 key, r and s even if the tag does not match
+pub fn decrypt_diagnostic(nonce: Vec<u8>, key: Vec<u8>, ciphertext: Vec<u8>, ad: Vec<u8>, tag: Vec<u8>) -> Result<ChaChaDiagnostic, String> {
+    check_arguments(&nonce, &key)?;
+    if tag.len() != 16 {
+        return Err(format!("Tag has {} bytes, expected 16", tag.len()));
+    }
+
+    let poly_key = chacha20_block(&key, 0, &nonce)[..32].to_vec();
+    let expected_tag = poly1305(&poly_key, &mac_data(&ad, &ciphertext));
+    let authentic = openssl::memcmp::eq(&expected_tag, &tag);
+    let plaintext = chacha20_xor(&key, 1, &nonce, &ciphertext);
+
+    let (r, s) = split_key(&poly_key);
+    Ok((authentic, plaintext, poly_key, r, s))
+}
+
+fn check_arguments(nonce: &[u8], key: &[u8]) -> Result<(), String> {
+    if key.len() != 32 {
+        return Err(format!("Key has {} bytes, ChaCha20 expects 32", key.len()));
+    }
+    if nonce.len() != 12 {
+        return Err(format!("Nonce has {} bytes, ChaCha20 expects 12", nonce.len()));
+    }
+    Ok(())
+}
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+fn words(bytes: &[u8]) -> impl Iterator<Item = u32> + '_ {
+    bytes.chunks(4).map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+}
+
+// State: constants, key, block counter, nonce. 20 rounds, then the initial state is added
+fn chacha20_block(key: &[u8], counter: u32, nonce: &[u8]) -> Vec<u8> {
+    let mut initial = [0u32; 16];
+    for (word, value) in initial.iter_mut().zip(CONSTANTS.into_iter().chain(words(key)).chain([counter]).chain(words(nonce))) {
+        *word = value;
+    }
+
+    let mut state = initial;
+    for _ in 0..10 {
+        // Column rounds
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        // Diagonal rounds
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+
+    state.iter().zip(initial).flat_map(|(word, start)| word.wrapping_add(start).to_le_bytes()).collect()
+}
+
+fn chacha20_xor(key: &[u8], counter: u32, nonce: &[u8], input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    for (i, chunk) in input.chunks(64).enumerate() {
+        let keystream = chacha20_block(key, counter.wrapping_add(i as u32), nonce);
+        output.extend(chunk.iter().zip(&keystream).map(|(x, k)| x ^ k));
+    }
+    output
+}
+
+// AD || pad || ciphertext || pad || len(AD) || len(ciphertext), lengths in bytes as little endian u64
+fn mac_data(ad: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let mut data = ad.to_vec();
+    data.resize(ad.len().next_multiple_of(16), 0);
+    data.extend_from_slice(ciphertext);
+    data.resize(data.len().next_multiple_of(16), 0);
+    data.extend_from_slice(&(ad.len() as u64).to_le_bytes());
+    data.extend_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+    data
+}
+
+// r is the first half of the key with the clamping bits cleared, s the second half
+fn split_key(poly_key: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let mut r = poly_key[..16].to_vec();
+    for i in [3, 7, 11, 15] {
+        r[i] &= 0x0f;
+    }
+    for i in [4, 8, 12] {
+        r[i] &= 0xfc;
+    }
+    (r, poly_key[16..].to_vec())
+}
+
+// Every 16 byte block gets a 0x01 byte appended and is accumulated as acc = (acc + block) * r mod 2^130 - 5,
+// the tag is acc + s mod 2^128
+fn poly1305(poly_key: &[u8], message: &[u8]) -> Vec<u8> {
+    let (r, s) = split_key(poly_key);
+    let r = BigUint::from_bytes_le(&r);
+    let s = BigUint::from_bytes_le(&s);
+    let p = (BigUint::one() << 130u32) - BigUint::from(5u32);
+
+    let mut accumulator = BigUint::ZERO;
+    for chunk in message.chunks(16) {
+        let mut block = chunk.to_vec();
+        block.push(0x01);
+        accumulator = ((accumulator + BigUint::from_bytes_le(&block)) * &r) % &p;
+    }
+
+    let mut tag = ((accumulator + s) % (BigUint::one() << 128u32)).to_bytes_le();
+    tag.resize(16, 0);
+    tag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::de_encode_base64::decode_str;
+
+    #[test]
+    fn chacha20_block_rfc8439() {
+        // RFC 8439, section 2.3.2
+        let key: Vec<u8> = (0..32).collect();
+        let nonce = vec![0, 0, 0, 0x09, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+        let expected = decode_str("EPHn5NE7WRVQD90foyBxxMfR9MczwGgDBCKqmsPUbE7SgmRGB5+qCRTC1wXZiwKitRKc0d4WTrnL0IPoolA8Tg==");
+
+        assert_eq!(chacha20_block(&key, 1, &nonce), expected);
+    }
+
+    #[test]
+    fn poly1305_rfc8439() {
+        // RFC 8439, section 2.5.2
+        let key = decode_str("hda+eFdVbTN/RFL+QtUGqAEDgIr7DbL9Sr/2r0FJ9Rs=");
+        let message = b"Cryptographic Forum Research Group";
+
+        assert_eq!(poly1305(&key, message), decode_str("qAYdwTBRNsbCK4uvDAEnqQ=="));
+    }
+
+    #[test]
+    fn encrypt_rfc8439() {
+        // RFC 8439, section 2.8.2
+        let key: Vec<u8> = (0x80..0xa0).collect();
+        let nonce = vec![0x07, 0, 0, 0, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47];
+        let ad = vec![0x50, 0x51, 0x52, 0x53, 0xc0, 0xc1, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7];
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".to_vec();
+
+        let (ciphertext, tag, poly_key, _, _) = encrypt(nonce.clone(), key.clone(), plaintext.clone(), ad.clone()).unwrap();
+        assert_eq!(ciphertext, decode_str("0xqNNGSOYNt7hq+8U+9+wqSt7VEpbgj+qeK1pzbuYtY9vqRejKlnEoL6+2naknKLGnHeCp4GCykF1qW2fs07NpLdvX8td4uMmAOu4ygJG1j6syTk+tZ1lFWFgItIMde8P/Te8I5Lep3ldtJlhs7GS2EW"));
+        assert_eq!(tag, decode_str("GuELWU8J4mp+kC7L0GAGkQ=="));
+        assert_eq!(poly_key, decode_str("e6wrJS20R68JtnpVpOlVhArh1nMQddnrKpN1eD7VU/8="));
+
+        assert_eq!(decrypt(nonce.clone(), key.clone(), ciphertext.clone(), ad.clone(), tag.clone()).unwrap(), plaintext);
+
+        let mut forged = tag;
+        forged[15] ^= 0x80;
+        assert_eq!(decrypt(nonce, key, ciphertext, ad, forged), Err(AeadError::AuthenticationFailed));
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert!(encrypt(vec![0u8; 12], vec![0u8; 16], Vec::new(), Vec::new()).is_err());
+        assert!(decrypt(vec![0u8; 8], vec![0u8; 32], Vec::new(), Vec::new(), vec![0u8; 16]).is_err());
+    }
+}
@@ -17,6 +17,12 @@ pub fn decode_vectors(blocks: Vec<String>) -> Vec<Vec<u8>> {
     a
 }
 
+// Decodes a base64 literal, for the test vectors of the other actions
+#[cfg(test)]
+pub fn decode_str(input: &str) -> Vec<u8> {
+    decode(input.to_string()).unwrap()
+}
+
 pub fn encode<T: AsRef<[u8]>>(byte_vect: T) -> String {
    general_purpose::STANDARD.encode(byte_vect)
 }
@@ -0,0 +1,145 @@
+use super::aes_sea_128;
+use super::block_modes::ctr;
+use super::aead::AeadError;
+
+// Ciphertext and tag
+type EaxOutput = (Vec<u8>, Vec<u8>);
+
+// EAX (Bellare, Rogaway, Wagner): N' = OMAC_0(nonce), H' = OMAC_1(AD), C = CTR_N'(plaintext),
+// tag = N' XOR OMAC_2(C) XOR H'. The nonce may have any length
+pub fn encrypt(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, plaintext: Vec<u8>, ad: Vec<u8>, tag_length: usize) -> Result<EaxOutput, String> {
+    check_arguments(&algorithm, &key, tag_length)?;
+
+    let nonce_mac = omac(&algorithm, &key, 0, &nonce)?;
+    let ciphertext = ctr(&algorithm, &key, &nonce_mac, &plaintext)?;
+    let tag = tag(&algorithm, &key, &nonce_mac, &ciphertext, &ad, tag_length)?;
+
+    Ok((ciphertext, tag))
+}
+
+// The tag covers the ciphertext, so it is checked before anything is decrypted
+pub fn decrypt(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, ciphertext: Vec<u8>, ad: Vec<u8>, tag: Vec<u8>, tag_length: usize) -> Result<Vec<u8>, AeadError> {
+    check_arguments(&algorithm, &key, tag_length)?;
+    if tag.len() != tag_length {
+        return Err(AeadError::InvalidArgument(format!("Tag has {} bytes, expected {}", tag.len(), tag_length)));
+    }
+
+    let nonce_mac = omac(&algorithm, &key, 0, &nonce)?;
+    let expected_tag = self::tag(&algorithm, &key, &nonce_mac, &ciphertext, &ad, tag_length)?;
+    if !openssl::memcmp::eq(&expected_tag, &tag) {
+        return Err(AeadError::AuthenticationFailed);
+    }
+
+    Ok(ctr(&algorithm, &key, &nonce_mac, &ciphertext)?)
+}
+
+fn check_arguments(algorithm: &str, key: &[u8], tag_length: usize) -> Result<(), String> {
+    aes_sea_128::check_key(algorithm, key)?;
+    if !(1..=16).contains(&tag_length) {
+        return Err(format!("Tag length of {} bytes is not between 1 and 16", tag_length));
+    }
+    Ok(())
+}
+
+fn tag(algorithm: &str, key: &Vec<u8>, nonce_mac: &[u8], ciphertext: &[u8], ad: &[u8], tag_length: usize) -> Result<Vec<u8>, String> {
+    let ad_mac = omac(algorithm, key, 1, ad)?;
+    let ciphertext_mac = omac(algorithm, key, 2, ciphertext)?;
+
+    Ok((0..tag_length).map(|i| nonce_mac[i] ^ ciphertext_mac[i] ^ ad_mac[i]).collect())
+}
+
+// OMAC_t(M) = CMAC([t]_128 || M)
+fn omac(algorithm: &str, key: &Vec<u8>, t: u8, message: &[u8]) -> Result<Vec<u8>, String> {
+    let mut input = vec![0u8; 16];
+    input[15] = t;
+    input.extend_from_slice(message);
+    cmac(algorithm, key, &input)
+}
+
+// Doubling in GF(2^128) with the bits in big endian order, as used for the CMAC subkeys
+fn double(block: &[u8]) -> Vec<u8> {
+    let value = u128::from_be_bytes(block.try_into().unwrap());
+    let doubled = (value << 1) ^ ((value >> 127) * 0x87);
+    doubled.to_be_bytes().to_vec()
+}
+
+// SP 800-38B: a complete last block is masked with K1, an incomplete one is padded with 10* and masked with K2
+fn cmac(algorithm: &str, key: &Vec<u8>, message: &[u8]) -> Result<Vec<u8>, String> {
+    let mode = &"encrypt".to_string();
+    let k1 = double(&aes_sea_128::execute(algorithm, mode, key, vec![0u8; 16])?);
+    let k2 = double(&k1);
+
+    let split = message.len().saturating_sub(1) / 16 * 16;
+    let (blocks, last) = message.split_at(split);
+
+    let mut last_block = last.to_vec();
+    let subkey = if last.len() == 16 {
+        &k1
+    } else {
+        last_block.push(0x80);
+        last_block.resize(16, 0);
+        &k2
+    };
+
+    let mut state = vec![0u8; 16];
+    for block in blocks.chunks(16) {
+        for (s, b) in state.iter_mut().zip(block) {
+            *s ^= b;
+        }
+        state = aes_sea_128::execute(algorithm, mode, key, state)?;
+    }
+    for ((s, b), k) in state.iter_mut().zip(&last_block).zip(subkey) {
+        *s ^= b ^ k;
+    }
+    aes_sea_128::execute(algorithm, mode, key, state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::de_encode_base64::decode_str;
+
+    #[test]
+    fn cmac_sp800_38b() {
+        // SP 800-38B, appendix D.1, examples 1 and 2
+        let key = decode_str("K34VFiiu0qar9xWICc9PPA==");
+
+        assert_eq!(cmac("aes128", &key, &[]).unwrap(), decode_str("ux1pKelZNyh/o30Sm3VnRg=="));
+        assert_eq!(cmac("aes128", &key, &decode_str("a8G+4i5An5bpPX4Rc5MXKg==")).unwrap(), decode_str("BwoWtGtNQUT3m92d0EoofA=="));
+    }
+
+    #[test]
+    fn encrypt_eax_paper() {
+        // Test vectors from the EAX paper (Bellare, Rogaway, Wagner)
+        let (ciphertext, tag) = encrypt("aes128".to_string(), decode_str("Yuxn+cOkpAf8sqjEkDGosw=="), decode_str("IzlS3uTV7V+bnG1v+A/0eA=="), Vec::new(), decode_str("a/uRT9B+rms="), 16).unwrap();
+        assert!(ciphertext.is_empty());
+        assert_eq!(tag, decode_str("4DeDDoOJ8nsCWi1lJ+edAQ=="));
+
+        let (ciphertext, tag) = encrypt("aes128".to_string(), decode_str("vsrwQ7CiPYQxlLqXLGbevQ=="), decode_str("kZRdP03L7gv0XvUiVfCVpA=="), decode_str("9/s="), decode_str("+jv9SAbrU/o="), 16).unwrap();
+        assert_eq!(ciphertext, decode_str("Gd0="));
+        assert_eq!(tag, decode_str("XEyTMQSdC9qwJ3QI9nln5Q=="));
+
+        let nonce = decode_str("cMPbTw0mNoQAoQ7QXSv/Xg==");
+        let key = decode_str("AfdK1kB38ucEwPYK2j3VIw==");
+        let ad = decode_str("I0o0Y8EmSsY=");
+        let (ciphertext, tag) = encrypt("aes128".to_string(), nonce.clone(), key.clone(), decode_str("GkfLSTM="), ad.clone(), 16).unwrap();
+        assert_eq!(ciphertext, decode_str("2FHVuuA="));
+        assert_eq!(tag, decode_str("OlnyOKI+ORmdySZmJsQPgA=="));
+
+        assert_eq!(decrypt("aes128".to_string(), nonce, key, ciphertext, ad, tag, 16).unwrap(), decode_str("GkfLSTM="));
+    }
+
+    #[test]
+    fn decrypt_sea128_truncated_tag() {
+        let key = decode_str("Xjq/GkpTSWoe3ZH0F+tjrQ==");
+        let nonce = decode_str("4gF+BtR3ku/PUQci");
+        let plaintext = decode_str("RGFzIGlzdCBlaW4gVGVzdCBtaXQgbWVociBhbHMgMTYgQnl0ZXM=");
+        let ad = decode_str("QUQtRGF0ZW4=");
+
+        let (ciphertext, tag) = encrypt("sea128".to_string(), nonce.clone(), key.clone(), plaintext.clone(), ad.clone(), 8).unwrap();
+        assert_eq!(tag.len(), 8);
+        assert_eq!(decrypt("sea128".to_string(), nonce.clone(), key.clone(), ciphertext.clone(), ad.clone(), tag.clone(), 8).unwrap(), plaintext);
+
+        assert_eq!(decrypt("sea128".to_string(), nonce, key, ciphertext, decode_str("QUQ="), tag, 8), Err(AeadError::AuthenticationFailed));
+    }
+}
@@ -1,15 +1,19 @@
-use super::{aes_sea_128, de_encode_base64, gf_operations};
+use super::{aead::AeadError, aes_sea_128, de_encode_base64, gf_operations};
 
-pub fn encrypt(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, plaintext: Vec<u8>, ad: Vec<u8>) -> (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>) {
-    let mode = &"encrypt".to_string();
-    let counter: u32 = 1;
+// Ciphertext, tag, L and H
+type GcmOutput = (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>);
 
-    let y = nonce.iter().chain(counter.to_be_bytes().iter()).cloned().collect(); // Concat nonce and counter
+pub fn encrypt(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, plaintext: Vec<u8>, ad: Vec<u8>, tag_length: usize) -> Result<GcmOutput, String> {
+    aes_sea_128::check_key(&algorithm, &key)?;
+    check_tag_length(tag_length)?;
+    check_nonce(&nonce)?;
+    let mode = &"encrypt".to_string();
 
-    let auth_key = aes_sea_128::execute(&algorithm, mode, &key, vec![0; 16]);
-    let y0_encrypted = aes_sea_128::execute(&algorithm, mode, &key, y);
+    let auth_key = aes_sea_128::execute(&algorithm, mode, &key, vec![0; 16])?;
+    let y0 = initial_counter(&nonce, &auth_key);
+    let y0_encrypted = aes_sea_128::execute(&algorithm, mode, &key, y0.clone())?;
 
-    let ciphertext = de_encrypt(algorithm, mode, plaintext, nonce, key); // Encrypt plaintext with algorithem in JSON 
+    let ciphertext = de_encrypt(algorithm, mode, plaintext, &y0, key)?; // Encrypt plaintext with algorithem in JSON 
     let (mut q, l) = ghash(ciphertext.clone(), auth_key.clone(), ad); // Run the GHASH function, gives back Q and L
 
     // XOR bytewise of Q and Y0
@@ -17,51 +21,107 @@ pub fn encrypt(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, plaintext: Vec<u
         q[i] ^= y0_encrypted[i];
     }
 
-    (ciphertext, q, l, auth_key)
+    q.truncate(tag_length); // Keep the most significant bytes of the tag
+
+    Ok((ciphertext, q, l, auth_key))
 }
 
-pub fn decrypt(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, ciphertext: Vec<u8>, ad: Vec<u8>, tag: Vec<u8>) -> (bool, Vec<u8>) {
-    let mode = &"encrypt".to_string();
-    let counter: u32 = 1;
+// The plaintext is only decrypted once the tag has been verified in constant time
+pub fn decrypt(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, ciphertext: Vec<u8>, ad: Vec<u8>, tag: Vec<u8>, tag_length: usize) -> Result<Vec<u8>, AeadError> {
+    let (expected_tag, y0) = expected_tag(&algorithm, &nonce, &key, &ciphertext, ad, &tag, tag_length)?;
+    if !openssl::memcmp::eq(&expected_tag, &tag) {
+        return Err(AeadError::AuthenticationFailed);
+    }
+
+    Ok(de_encrypt(algorithm, &"encrypt".to_string(), ciphertext, &y0, key)?)
+}
 
-    let y = nonce.iter().chain(counter.to_be_bytes().iter()).cloned().collect(); // Concat nonce and counter
+// Unsafe diagnostic mode for analysis: the plaintext is returned even if the tag does not match
+pub fn decrypt_diagnostic(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, ciphertext: Vec<u8>, ad: Vec<u8>, tag: Vec<u8>, tag_length: usize) -> Result<(bool, Vec<u8>), String> {
+    let (expected_tag, y0) = expected_tag(&algorithm, &nonce, &key, &ciphertext, ad, &tag, tag_length)?;
+    let authentic = openssl::memcmp::eq(&expected_tag, &tag);
+
+    let plaintext = de_encrypt(algorithm, &"encrypt".to_string(), ciphertext, &y0, key)?; // Decrypt plaintext with algorithem in JSON
+
+    Ok((authentic, plaintext))
+}
+
+// Checks the arguments of a decryption and computes the tag the ciphertext should have, along with Y0
+fn expected_tag(algorithm: &str, nonce: &[u8], key: &Vec<u8>, ciphertext: &[u8], ad: Vec<u8>, tag: &[u8], tag_length: usize) -> Result<(Vec<u8>, Vec<u8>), String> {
+    aes_sea_128::check_key(algorithm, key)?;
+    check_tag_length(tag_length)?;
+    check_nonce(nonce)?;
+    if tag.len() != tag_length {
+        return Err(format!("Tag has {} bytes, expected {}", tag.len(), tag_length));
+    }
+    let mode = &"encrypt".to_string();
 
-    let auth_key = aes_sea_128::execute(&algorithm, mode, &key, vec![0; 16]);
-    let y0_encrypted = aes_sea_128::execute(&algorithm, mode, &key, y);
+    let auth_key = aes_sea_128::execute(algorithm, mode, key, vec![0; 16])?;
+    let y0 = initial_counter(nonce, &auth_key);
+    let y0_encrypted = aes_sea_128::execute(algorithm, mode, key, y0.clone())?;
 
-    let (mut q, _l) = ghash(ciphertext.clone(), auth_key.clone(), ad); // Run the GHASH function, gives back Q and L
+    let (mut q, _l) = ghash(ciphertext.to_vec(), auth_key, ad); // Run the GHASH function, gives back Q and L
 
     // XOR bytewise of Q and Y0
-    for i in 0..q.len() {
-        q[i] ^= y0_encrypted[i];
+    for (q, y) in q.iter_mut().zip(&y0_encrypted) {
+        *q ^= y;
+    }
+
+    q.truncate(tag_length); // Keep the most significant bytes of the tag
+    Ok((q, y0))
+}
+
+// SP 800-38D allows tags of 4 to 16 bytes
+pub fn check_tag_length(tag_length: usize) -> Result<(), String> {
+    if !(4..=16).contains(&tag_length) {
+        return Err(format!("Tag length of {} bytes is not between 4 and 16", tag_length));
+    }
+    Ok(())
+}
+
+// SP 800-38D requires an IV of at least one bit, any other length is hashed into Y0
+pub fn check_nonce(nonce: &[u8]) -> Result<(), String> {
+    if nonce.is_empty() {
+        return Err("Nonce must not be empty".to_string());
+    }
+    Ok(())
+}
+
+// Y0 is nonce || 1 for 96 bit nonces, every other length is hashed: GHASH(nonce || pad || 0^64 || len(nonce))
+pub fn initial_counter(nonce: &[u8], auth_key: &Vec<u8>) -> Vec<u8> {
+    if nonce.len() == 12 {
+        let counter: u32 = 1;
+        return nonce.iter().chain(counter.to_be_bytes().iter()).cloned().collect(); // Concat nonce and counter
     }
 
-    let plaintext = de_encrypt(algorithm, mode, ciphertext, nonce, key); // Decrypt plaintext with algorithem in JSON 
-    
-    // Check if tag is authentic
-    let authentic: bool;
-    if q == tag {
-        authentic = true;
-    } else {
-        authentic = false;
+    let mut q: Vec<u8> = vec![0; 16];
+    for chunk in nonce.chunks(16) {
+        q = ghash_round(q, chunk, auth_key);
     }
 
-    (authentic, plaintext)
+    let length_block: Vec<u8> = [0u8; 8].iter().chain(((nonce.len() * 8) as u64).to_be_bytes().iter()).cloned().collect();
+    ghash_round(q, &length_block, auth_key)
 }
 
-fn de_encrypt(algorithm: String, mode: &String ,plaintext: Vec<u8>, nonce: Vec<u8>, key: Vec<u8>) -> Vec<u8> {
+// Increment the last 32 bits of the counter block, the first 96 bits stay untouched
+pub fn increment_counter(y0: &[u8], increment: u32) -> Vec<u8> {
+    let counter = u32::from_be_bytes(y0[12..16].try_into().unwrap()).wrapping_add(increment);
+    y0[..12].iter().chain(counter.to_be_bytes().iter()).cloned().collect()
+}
+
+fn de_encrypt(algorithm: String, mode: &String ,plaintext: Vec<u8>, y0: &[u8], key: Vec<u8>) -> Result<Vec<u8>, String> {
 
     let mut ciphertext: Vec<u8> = Vec::new();
 
     // Seperate the plaintext (byte vector) into chunks of 16 byte and iterate over them 
     for plaintext in plaintext.chunks(16).enumerate() {
-        let counter: u32 = plaintext.0 as u32 + 2;
+        let counter: u32 = plaintext.0 as u32 + 1;
         let mut plaintext = plaintext.1.to_vec();
 
-        let y = nonce.iter().chain(counter.to_be_bytes().iter()).cloned().collect();
+        let y = increment_counter(y0, counter);
 
         // En- or decrypt the chunk
-        let y_encrypted = aes_sea_128::execute(&algorithm, mode, &key, y);
+        let y_encrypted = aes_sea_128::execute(&algorithm, mode, &key, y)?;
 
         // XOR the chunk and the encrypted tweak
         for i in 0..plaintext.len() {
@@ -72,7 +132,7 @@ fn de_encrypt(algorithm: String, mode: &String ,plaintext: Vec<u8>, nonce: Vec<u
         ciphertext.append(&mut plaintext);
 
     }
-    ciphertext
+    Ok(ciphertext)
 }
 
 pub fn ghash(ciphertext: Vec<u8>, auth_key: Vec<u8>, ad: Vec<u8>) -> (Vec<u8>, Vec<u8>) {
@@ -99,7 +159,7 @@ pub fn ghash(ciphertext: Vec<u8>, auth_key: Vec<u8>, ad: Vec<u8>) -> (Vec<u8>, V
     (q, l)
 }
 
-fn ghash_round(mut q: Vec<u8>, chunk: &[u8], auth_key: &Vec<u8>) -> Vec<u8> {
+pub fn ghash_round(mut q: Vec<u8>, chunk: &[u8], auth_key: &Vec<u8>) -> Vec<u8> {
     let mut buffer = [0u8; 16]; // Prepare a 16-byte buffer
     let semantic = "gcm".to_string();
 
@@ -133,7 +193,7 @@ mod tests {
         let h = de_encode_base64::decode("xhFcAUT66qWIpYz+Ch5ujw==".to_string()).unwrap();
         let expected = (ciphertext, tag, l, h);
 
-        let result = encrypt(algorithm, nonce, key, plaintext, ad);
+        let result = encrypt(algorithm, nonce, key, plaintext, ad, 16).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -153,7 +213,7 @@ mod tests {
         let h = de_encode_base64::decode("xhFcAUT66qWIpYz+Ch5ujw==".to_string()).unwrap();
         let expected = (ciphertext, tag, l, h);
 
-        let result = encrypt(algorithm, nonce, key, plaintext, ad);
+        let result = encrypt(algorithm, nonce, key, plaintext, ad, 16).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -173,7 +233,7 @@ mod tests {
         let h = de_encode_base64::decode("xhFcAUT66qWIpYz+Ch5ujw==".to_string()).unwrap();
         let expected = (ciphertext, tag, l, h);
 
-        let result = encrypt(algorithm, nonce, key, plaintext, ad);
+        let result = encrypt(algorithm, nonce, key, plaintext, ad, 16).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -193,7 +253,7 @@ mod tests {
         let h = de_encode_base64::decode("xhFcAUT66qWIpYz+Ch5ujw==".to_string()).unwrap();
         let expected = (ciphertext, tag, l, h);
 
-        let result = encrypt(algorithm, nonce, key, plaintext, ad);
+        let result = encrypt(algorithm, nonce, key, plaintext, ad, 16).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -212,7 +272,7 @@ mod tests {
         let authentic = false;
         let expected = (authentic, plaintext);
 
-        let result = decrypt(algorithm, nonce, key, ciphertext, ad, tag);
+        let result = decrypt_diagnostic(algorithm, nonce, key, ciphertext, ad, tag, 16).unwrap();
         assert_eq!(result, expected);
     }    
 
@@ -232,7 +292,7 @@ mod tests {
         let h = de_encode_base64::decode("Bu6ywbsUKlpmZXMQyuGAng==".to_string()).unwrap();
         let expected = (ciphertext, tag, l, h);
 
-        let result = encrypt(algorithm, nonce, key, plaintext, ad);
+        let result = encrypt(algorithm, nonce, key, plaintext, ad, 16).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -252,7 +312,7 @@ mod tests {
         let h = de_encode_base64::decode("Bu6ywbsUKlpmZXMQyuGAng==".to_string()).unwrap();
         let expected = (ciphertext, tag, l, h);
 
-        let result = encrypt(algorithm, nonce, key, plaintext, ad);
+        let result = encrypt(algorithm, nonce, key, plaintext, ad, 16).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -272,7 +332,7 @@ mod tests {
         let h = de_encode_base64::decode("Bu6ywbsUKlpmZXMQyuGAng==".to_string()).unwrap();
         let expected = (ciphertext, tag, l, h);
 
-        let result = encrypt(algorithm, nonce, key, plaintext, ad);
+        let result = encrypt(algorithm, nonce, key, plaintext, ad, 16).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -292,7 +352,7 @@ mod tests {
         let h = de_encode_base64::decode("Bu6ywbsUKlpmZXMQyuGAng==".to_string()).unwrap();
         let expected = (ciphertext, tag, l, h);
 
-        let result = encrypt(algorithm, nonce, key, plaintext, ad);
+        let result = encrypt(algorithm, nonce, key, plaintext, ad, 16).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -311,8 +371,156 @@ mod tests {
         let authentic = true;
         let expected = (authentic, plaintext);
 
-        let result = decrypt(algorithm, nonce, key, ciphertext, ad, tag);
+        let result = decrypt_diagnostic(algorithm, nonce, key, ciphertext, ad, tag, 16).unwrap();
         assert_eq!(result, expected);
     }
-}
 
+    #[test]
+    fn encrypt_aes128_64bit_nonce() {
+        // Test Case 5 of the GCM specification (McGrew, Viega), an 8 byte IV
+        let algorithm = "aes128".to_string();
+        let nonce= de_encode_base64::decode("yv66vvrO260=".to_string()).unwrap();
+        let key= de_encode_base64::decode("/v/pkoZlcxxtao+UZzCDCA==".to_string()).unwrap();
+        let plaintext= de_encode_base64::decode("2TEyJfiEBuWlWQnFr/UmmoanqVMVNPfaLkwwPYoxinIcPAyVlWgJUy/PDiRJprUlsWrt9aoN5le6Y3s5".to_string()).unwrap();
+        let ad= de_encode_base64::decode("/u36zt6tvu/+7frO3q2+76ut2tI=".to_string()).unwrap();
+
+        // Expected Output preparation
+        let ciphertext = de_encode_base64::decode("YTU7TCgGk0p3f/UfoipHVWmbKnFPzcb4N2bl+XtsdCNzgGkA5J8ksisJdUTUiWtCSYm14eusDwfCP0WY".to_string()).unwrap();
+        let tag = de_encode_base64::decode("NhLS5547B4VWG+FKrKL8yw==".to_string()).unwrap();
+
+        let result = encrypt(algorithm.clone(), nonce.clone(), key.clone(), plaintext.clone(), ad.clone(), 16).unwrap();
+        assert_eq!((result.0.clone(), result.1.clone()), (ciphertext.clone(), tag.clone()));
+
+        let result = decrypt_diagnostic(algorithm, nonce, key, ciphertext, ad, tag, 16).unwrap();
+        assert_eq!(result, (true, plaintext));
+    }
+
+    #[test]
+    fn encrypt_aes128_480bit_nonce() {
+        // GCM spec Test Case 6: a 60 byte IV
+        let algorithm = "aes128".to_string();
+        let nonce= de_encode_base64::decode("kxMiXfiEBuVVkJxa/1Jpqmp6lThTT32h5MMD0qMYpyjDwMlRVoCVOfzw4kKaa1JUFq7b9aDealemN7Ob".to_string()).unwrap();
+        let key= de_encode_base64::decode("/v/pkoZlcxxtao+UZzCDCA==".to_string()).unwrap();
+        let plaintext= de_encode_base64::decode("2TEyJfiEBuWlWQnFr/UmmoanqVMVNPfaLkwwPYoxinIcPAyVlWgJUy/PDiRJprUlsWrt9aoN5le6Y3s5".to_string()).unwrap();
+        let ad= de_encode_base64::decode("/u36zt6tvu/+7frO3q2+76ut2tI=".to_string()).unwrap();
+
+        // Expected Output preparation
+        let ciphertext = de_encode_base64::decode("jOJJmGJWFbYDoDOsoT+4lL6REqXDohGouiYqPMp+LKcB5Kmk+6Q8kMzcsoHUjHxv1ih10qykFwNMNK7l".to_string()).unwrap();
+        let tag = de_encode_base64::decode("YZzFrv/+C/pGKvQ8FpnQUA==".to_string()).unwrap();
+
+        let result = encrypt(algorithm, nonce, key, plaintext, ad, 16).unwrap();
+        assert_eq!((result.0, result.1), (ciphertext, tag));
+    }
+
+    #[test]
+    fn encrypt_aes128_128bit_nonce() {
+        // A 16 byte IV has to be hashed as well, it is not used as the counter block directly
+        let algorithm = "aes128".to_string();
+        let nonce= de_encode_base64::decode("AAECAwQFBgcICQoLDA0ODw==".to_string()).unwrap();
+        let key= de_encode_base64::decode("/v/pkoZlcxxtao+UZzCDCA==".to_string()).unwrap();
+        let plaintext= de_encode_base64::decode("2TEyJfiEBuWlWQnFr/UmmoanqVMVNPfaLkwwPYoxinIcPAyVlWgJUy/PDiRJprUlsWrt9aoN5le6Y3s5".to_string()).unwrap();
+        let ad= de_encode_base64::decode("/u36zt6tvu/+7frO3q2+76ut2tI=".to_string()).unwrap();
+
+        // Expected Output preparation
+        let ciphertext = de_encode_base64::decode("kzMmn3GQdHwMQPTmiyZLzLQl638BZo2oHjL3xRmzdSSSl+otw/iMOisccDc4Md83lZ2wU2QvsAmkcQVV".to_string()).unwrap();
+        let tag = de_encode_base64::decode("4pgsi5j91eqn2fUpkNiXiw==".to_string()).unwrap();
+
+        let result = encrypt(algorithm, nonce, key, plaintext, ad, 16).unwrap();
+        assert_eq!((result.0, result.1), (ciphertext, tag));
+    }
+
+    #[test]
+    fn encrypt_aes256_normal() {
+        // Test Case 16 of the GCM specification (McGrew, Viega)
+        let algorithm = "aes256".to_string();
+        let nonce= de_encode_base64::decode("yv66vvrO263eyviI".to_string()).unwrap();
+        let key= de_encode_base64::decode("/v/pkoZlcxxtao+UZzCDCP7/6ZKGZXMcbWqPlGcwgwg=".to_string()).unwrap();
+        let plaintext= de_encode_base64::decode("2TEyJfiEBuWlWQnFr/UmmoanqVMVNPfaLkwwPYoxinIcPAyVlWgJUy/PDiRJprUlsWrt9aoN5le6Y3s5".to_string()).unwrap();
+        let ad= de_encode_base64::decode("/u36zt6tvu/+7frO3q2+76ut2tI=".to_string()).unwrap();
+
+        // Expected Output preparation
+        let ciphertext = de_encode_base64::decode("Ui3B8JlWfQf0fzejKoRCfWQ6jNy/5cDJdZiivSVV0aqMsI5IWQ27PaewixBWgog4xfYeY5O6egq8yfZi".to_string()).unwrap();
+        let tag = de_encode_base64::decode("dvxuzg9OF2jN34hTuy1VGw==".to_string()).unwrap();
+
+        let result = encrypt(algorithm, nonce, key, plaintext, ad, 16).unwrap();
+        assert_eq!((result.0, result.1), (ciphertext, tag));
+    }
+
+    #[test]
+    fn encrypt_key_length_mismatch() {
+        let nonce= de_encode_base64::decode("yv66vvrO263eyviI".to_string()).unwrap();
+        let key= de_encode_base64::decode("Xjq/GkpTSWoe3ZH0F+tjrQ==".to_string()).unwrap();
+
+        assert!(encrypt("aes256".to_string(), nonce.clone(), key.clone(), vec![], vec![], 16).is_err());
+        assert!(decrypt_diagnostic("sea192".to_string(), nonce, key, vec![], vec![], vec![0; 16], 16).is_err());
+    }
+
+    #[test]
+    fn truncated_tags() {
+        let algorithm = "aes128".to_string();
+        let nonce= de_encode_base64::decode("4gF+BtR3ku/PUQci".to_string()).unwrap();
+        let key= de_encode_base64::decode("Xjq/GkpTSWoe3ZH0F+tjrQ==".to_string()).unwrap();
+        let plaintext= de_encode_base64::decode("RGFzIGlzdCBlaW4gVGVzdA==".to_string()).unwrap();
+        let ad= de_encode_base64::decode("QUQtRGF0ZW4=".to_string()).unwrap();
+        let full_tag = de_encode_base64::decode("Mp0APJb/ZIURRwQlMgNN/w==".to_string()).unwrap();
+
+        for tag_length in [4, 8, 12] {
+            let (ciphertext, tag, _, _) = encrypt(algorithm.clone(), nonce.clone(), key.clone(), plaintext.clone(), ad.clone(), tag_length).unwrap();
+            assert_eq!(tag, full_tag[..tag_length]);
+
+            let result = decrypt_diagnostic(algorithm.clone(), nonce.clone(), key.clone(), ciphertext.clone(), ad.clone(), tag.clone(), tag_length).unwrap();
+            assert_eq!(result, (true, plaintext.clone()));
+
+            let mut forged = tag;
+            forged[tag_length - 1] ^= 1;
+            let result = decrypt_diagnostic(algorithm.clone(), nonce.clone(), key.clone(), ciphertext, ad.clone(), forged, tag_length).unwrap();
+            assert!(!result.0);
+        }
+    }
+
+    #[test]
+    fn invalid_tag_lengths() {
+        let algorithm = "aes128".to_string();
+        let nonce= de_encode_base64::decode("4gF+BtR3ku/PUQci".to_string()).unwrap();
+        let key= de_encode_base64::decode("Xjq/GkpTSWoe3ZH0F+tjrQ==".to_string()).unwrap();
+
+        assert!(encrypt(algorithm.clone(), nonce.clone(), key.clone(), vec![], vec![], 3).is_err());
+        assert!(encrypt(algorithm.clone(), nonce.clone(), key.clone(), vec![], vec![], 17).is_err());
+
+        // A tag that does not have the announced length is rejected
+        assert!(decrypt_diagnostic(algorithm, nonce, key, vec![], vec![], vec![0; 16], 8).is_err());
+    }
+
+    #[test]
+    fn empty_nonce() {
+        let algorithm = "aes128".to_string();
+        let key= de_encode_base64::decode("Xjq/GkpTSWoe3ZH0F+tjrQ==".to_string()).unwrap();
+
+        assert!(encrypt(algorithm.clone(), vec![], key.clone(), vec![], vec![], 16).is_err());
+        assert!(matches!(decrypt(algorithm.clone(), vec![], key.clone(), vec![], vec![], vec![0; 16], 16), Err(AeadError::InvalidArgument(_))));
+        assert!(decrypt_diagnostic(algorithm, vec![], key, vec![], vec![], vec![0; 16], 16).is_err());
+    }
+
+    #[test]
+    fn safe_decrypt() {
+        let algorithm = "aes128".to_string();
+        let nonce= de_encode_base64::decode("4gF+BtR3ku/PUQci".to_string()).unwrap();
+        let key= de_encode_base64::decode("Xjq/GkpTSWoe3ZH0F+tjrQ==".to_string()).unwrap();
+        let ciphertext= de_encode_base64::decode("ET3RmvH/Hbuxba63EuPRrw==".to_string()).unwrap();
+        let ad= de_encode_base64::decode("QUQtRGF0ZW4=".to_string()).unwrap();
+        let tag= de_encode_base64::decode("Mp0APJb/ZIURRwQlMgNN/w==".to_string()).unwrap();
+
+        let plaintext = de_encode_base64::decode("RGFzIGlzdCBlaW4gVGVzdA==".to_string()).unwrap();
+        let result = decrypt(algorithm.clone(), nonce.clone(), key.clone(), ciphertext.clone(), ad.clone(), tag.clone(), 16);
+        assert_eq!(result, Ok(plaintext));
+
+        // A forged tag gives no plaintext at all
+        let mut forged = tag;
+        forged[15] ^= 0x80;
+        let result = decrypt(algorithm.clone(), nonce.clone(), key.clone(), ciphertext.clone(), ad.clone(), forged, 16);
+        assert_eq!(result, Err(AeadError::AuthenticationFailed));
+
+        let result = decrypt(algorithm, nonce, key, ciphertext, ad, vec![0; 8], 16);
+        assert!(matches!(result, Err(AeadError::InvalidArgument(_))));
+    }
+}
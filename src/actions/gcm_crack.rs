@@ -1,11 +1,13 @@
 use super::{de_encode_base64, gcm, gf_operations, gfpoly_operations};
+use super::gfpoly_operations::PolyRng;
 
 pub fn execute(
     _nonce: Vec<u8>, 
     m1: (Vec<u8>, Vec<u8>, Vec<u8>), 
     m2: (Vec<u8>, Vec<u8>, Vec<u8>), 
     m3: (Vec<u8>, Vec<u8>, Vec<u8>), 
-    forgery: (Vec<u8>, Vec<u8>)
+    forgery: (Vec<u8>, Vec<u8>),
+    rng: &mut dyn PolyRng
     ) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
 
     // Create L Block for m1
@@ -33,27 +35,8 @@ pub fn execute(
     // Initialize m1.ciphertext + m2.ciphertext
     let new_poly = gfpoly_operations::add(&m1_whole, &m2_whole);
     
-    let sff = gfpoly_operations::sff(&new_poly);
-
-    let mut ddf: Vec<(Vec<Vec<u8>>, u128)> = Vec::new();
-    for poly in sff {
-        ddf.extend(gfpoly_operations::ddf(&poly.0));
-    }
-
     // Get all H-Candiadtates
-    let mut h_candidates: Vec<Vec<u8>> = Vec::new();
-    for poly in ddf {
-        if poly.1 == 1 {
-            if poly.0.len() == 2 {
-                h_candidates.push(poly.0[0].clone());
-                continue;
-            }
-            let edf = gfpoly_operations::edf(&poly.0, poly.1 as usize);
-            for h in edf {
-                h_candidates.push(h[0].clone());
-            }
-        }
-    }
+    let h_candidates = gfpoly_operations::roots(&new_poly, rng);
 
     // Get the coressponding H_ek to the H-Canditates
     let mut h_ek: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
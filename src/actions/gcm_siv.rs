@@ -0,0 +1,188 @@
+use super::{aes_sea_128, gcm, gf_operations};
+use super::aead::AeadError;
+
+// Ciphertext and tag
+type GcmSivOutput = (Vec<u8>, Vec<u8>);
+
+// The element x of GF(2^128) as a block in gcm semantic
+const X: [u8; 16] = [0x40, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+// Encrypting the same data twice under one nonce only reveals that the data is equal
+pub fn encrypt(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, plaintext: Vec<u8>, ad: Vec<u8>) -> Result<GcmSivOutput, String> {
+    check_arguments(&algorithm, &nonce, &key)?;
+    let (auth_key, encryption_key) = derive_keys(&algorithm, &nonce, &key)?;
+
+    let tag = synthetic_iv(&algorithm, &nonce, &auth_key, &encryption_key, &plaintext, &ad)?;
+    let ciphertext = ctr(&algorithm, &encryption_key, &tag, plaintext)?;
+
+    Ok((ciphertext, tag))
+}
+
+// The tag is computed over the plaintext, so the plaintext is only handed out after it has been checked
+pub fn decrypt(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, ciphertext: Vec<u8>, ad: Vec<u8>, tag: Vec<u8>) -> Result<Vec<u8>, AeadError> {
+    check_arguments(&algorithm, &nonce, &key)?;
+    if tag.len() != 16 {
+        return Err(AeadError::InvalidArgument(format!("Tag has {} bytes, expected 16", tag.len())));
+    }
+    let (auth_key, encryption_key) = derive_keys(&algorithm, &nonce, &key)?;
+
+    let plaintext = ctr(&algorithm, &encryption_key, &tag, ciphertext)?;
+    let expected_tag = synthetic_iv(&algorithm, &nonce, &auth_key, &encryption_key, &plaintext, &ad)?;
+
+    if !openssl::memcmp::eq(&expected_tag, &tag) {
+        return Err(AeadError::AuthenticationFailed);
+    }
+    Ok(plaintext)
+}
+
+// RFC 8452 only defines 128 and 256 bit keys and 96 bit nonces
+fn check_arguments(algorithm: &str, nonce: &[u8], key: &[u8]) -> Result<(), String> {
+    aes_sea_128::check_key(algorithm, key)?;
+    if key.len() == 24 {
+        return Err(format!("GCM-SIV is not defined for {}", algorithm));
+    }
+    if nonce.len() != 12 {
+        return Err(format!("Nonce has {} bytes, GCM-SIV expects 12", nonce.len()));
+    }
+    Ok(())
+}
+
+// Per-nonce keys: the first 8 bytes of E(key, LE32(i) || nonce) for i = 0, 1, ...
+// Two blocks form the authentication key, the encryption key needs as many bytes as the key itself
+fn derive_keys(algorithm: &str, nonce: &[u8], key: &Vec<u8>) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let mode = &"encrypt".to_string();
+    let blocks = 2 + key.len() / 8;
+
+    let mut derived: Vec<u8> = Vec::with_capacity(8 * blocks);
+    for i in 0..blocks as u32 {
+        let input: Vec<u8> = i.to_le_bytes().iter().chain(nonce).cloned().collect();
+        derived.extend(aes_sea_128::execute(algorithm, mode, key, input)?.into_iter().take(8));
+    }
+
+    Ok((derived[..16].to_vec(), derived[16..].to_vec()))
+}
+
+// Tag = E(encryption key, POLYVAL(AD, plaintext, lengths) XOR nonce) with the highest bit cleared
+fn synthetic_iv(algorithm: &str, nonce: &[u8], auth_key: &[u8], encryption_key: &Vec<u8>, plaintext: &[u8], ad: &[u8]) -> Result<Vec<u8>, String> {
+    // AD and plaintext are padded separately and followed by their little endian bit lengths
+    let mut input = ad.to_vec();
+    input.resize(ad.len().next_multiple_of(16), 0);
+    input.extend_from_slice(plaintext);
+    input.resize(input.len().next_multiple_of(16), 0);
+    input.extend_from_slice(&((ad.len() * 8) as u64).to_le_bytes());
+    input.extend_from_slice(&((plaintext.len() * 8) as u64).to_le_bytes());
+
+    let mut s = polyval(auth_key, &input);
+
+    for (s, n) in s.iter_mut().zip(nonce) {
+        *s ^= n;
+    }
+    s[15] &= 0x7f;
+
+    aes_sea_128::execute(algorithm, &"encrypt".to_string(), encryption_key, s)
+}
+
+// POLYVAL works in the field of the reversed polynomial x^128 + x^127 + x^126 + x^121 + 1.
+// RFC 8452, appendix A maps it onto GHASH: POLYVAL(H, X_1, ..., X_n) =
+// ByteReverse(GHASH(ByteReverse(H) * x, ByteReverse(X_1), ..., ByteReverse(X_n)))
+fn polyval(auth_key: &[u8], input: &[u8]) -> Vec<u8> {
+    let reversed_key: Vec<u8> = auth_key.iter().rev().cloned().collect();
+    let h = gf_operations::gfmul(&"gcm".to_string(), reversed_key, X.to_vec());
+
+    let mut q = vec![0u8; 16];
+    for chunk in input.chunks(16) {
+        let mut block = chunk.to_vec();
+        block.resize(16, 0);
+        block.reverse();
+        q = gcm::ghash_round(q, &block, &h);
+    }
+    q.reverse();
+    q
+}
+
+// Counter mode starting at the tag with its highest bit set, only the first 32 bits count (little endian)
+fn ctr(algorithm: &str, encryption_key: &Vec<u8>, tag: &[u8], input: Vec<u8>) -> Result<Vec<u8>, String> {
+    let mode = &"encrypt".to_string();
+    let mut counter_block = tag.to_vec();
+    counter_block[15] |= 0x80;
+    let initial = u32::from_le_bytes(counter_block[..4].try_into().unwrap());
+
+    let mut output = Vec::with_capacity(input.len());
+    for (i, chunk) in input.chunks(16).enumerate() {
+        let counter = initial.wrapping_add(i as u32);
+        counter_block[..4].copy_from_slice(&counter.to_le_bytes());
+
+        let keystream = aes_sea_128::execute(algorithm, mode, encryption_key, counter_block.clone())?;
+        output.extend(chunk.iter().zip(&keystream).map(|(c, k)| c ^ k));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::de_encode_base64::decode_str;
+
+    #[test]
+    fn polyval_rfc8452() {
+        // RFC 8452, appendix A
+        let h = decode_str("JWKTR1iSQnYdMfgmukt1ew==");
+        let x = decode_str("T0+VZoyD37ZAF2K7LQGiYtGiTd0nIdAGu+RfINPJ82I=");
+        let expected = decode_str("96O0e4RhGfrlt4Zs9eW3fg==");
+
+        assert_eq!(polyval(&h, &x), expected);
+    }
+
+    #[test]
+    fn encrypt_aes128_rfc8452() {
+        // RFC 8452, appendix C.1
+        let key = decode_str("AQAAAAAAAAAAAAAAAAAAAA==");
+        let nonce = decode_str("AwAAAAAAAAAAAAAA");
+
+        let (ciphertext, tag) = encrypt("aes128".to_string(), nonce.clone(), key.clone(), Vec::new(), Vec::new()).unwrap();
+        assert!(ciphertext.is_empty());
+        assert_eq!(tag, decode_str("3CDi2D8lcFu0nkOeylbeJQ=="));
+
+        let (ciphertext, tag) = encrypt("aes128".to_string(), nonce.clone(), key.clone(), decode_str("AQAAAAAAAAA="), Vec::new()).unwrap();
+        assert_eq!(ciphertext, decode_str("tdg5MwrHt4Y="));
+        assert_eq!(tag, decode_str("V4eC//YBO4FbKHwiSTo2TA=="));
+
+        let (ciphertext, tag) = encrypt("aes128".to_string(), nonce, key, decode_str("AgAAAAAAAAAAAAAAAAAAAA=="), decode_str("AQ==")).unwrap();
+        assert_eq!(ciphertext, decode_str("4rDF2nmpAcF0X3AFJcszWw=="));
+        assert_eq!(tag, decode_str("j4k27AOeTku5fr2MRFdEHw=="));
+    }
+
+    #[test]
+    fn encrypt_aes256_rfc8452() {
+        // RFC 8452, appendix C.2
+        let key = decode_str("AQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=");
+        let nonce = decode_str("AwAAAAAAAAAAAAAA");
+
+        let (ciphertext, tag) = encrypt("aes256".to_string(), nonce, key, decode_str("AQAAAAAAAAA="), Vec::new()).unwrap();
+        assert_eq!(ciphertext, decode_str("wu8yjlxxyDs="));
+        assert_eq!(tag, decode_str("hDEiEw9zZLdh4Ll0J+PfKA=="));
+    }
+
+    #[test]
+    fn decrypt_sea128_roundtrip() {
+        let key = decode_str("Xjq/GkpTSWoe3ZH0F+tjrQ==");
+        let nonce = decode_str("4gF+BtR3ku/PUQci");
+        let plaintext = decode_str("RGFzIGlzdCBlaW4gVGVzdCBtaXQgbWVociBhbHMgMTYgQnl0ZXM=");
+        let ad = decode_str("QUQtRGF0ZW4=");
+
+        let (ciphertext, tag) = encrypt("sea128".to_string(), nonce.clone(), key.clone(), plaintext.clone(), ad.clone()).unwrap();
+        let result = decrypt("sea128".to_string(), nonce.clone(), key.clone(), ciphertext.clone(), ad.clone(), tag.clone()).unwrap();
+        assert_eq!(result, plaintext);
+
+        let mut forged = ciphertext;
+        forged[0] ^= 1;
+        assert_eq!(decrypt("sea128".to_string(), nonce, key, forged, ad, tag), Err(AeadError::AuthenticationFailed));
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let key = vec![0u8; 24];
+        assert!(encrypt("aes192".to_string(), vec![0u8; 12], key, Vec::new(), Vec::new()).is_err());
+        assert!(encrypt("aes128".to_string(), vec![0u8; 8], vec![0u8; 16], Vec::new(), Vec::new()).is_err());
+    }
+}
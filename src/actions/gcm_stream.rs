@@ -0,0 +1,360 @@
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use super::{aes_sea_128, gcm};
+
+// Tag, L and H, the ciphertext has been handed out by the update calls
+type StreamOutput = (Vec<u8>, Vec<u8>, Vec<u8>);
+
+const FILE_CHUNK_SIZE: usize = 1 << 16;
+
+// SP 800-38D limits the plaintext to 2^32 - 2 blocks, beyond that the 32 bit counter would wrap to Y0
+const MAX_BLOCKS: u64 = (1 << 32) - 2;
+
+// State shared by both directions: the GHASH state Q with the bytes of a block that is not
+// complete yet, and the key stream left over from the last counter block
+struct GcmStream {
+    algorithm: String,
+    key: Vec<u8>,
+    auth_key: Vec<u8>,
+    y0: Vec<u8>,
+    y0_encrypted: Vec<u8>,
+    tag_length: usize,
+    q: Vec<u8>,
+    pending: Vec<u8>,
+    keystream: Vec<u8>,
+    counter: u32,
+    ad_length: u64,
+    text_length: u64,
+    ad_finished: bool
+}
+
+impl GcmStream {
+    fn new(algorithm: String, key: Vec<u8>, nonce: &[u8], tag_length: usize) -> Result<Self, String> {
+        aes_sea_128::check_key(&algorithm, &key)?;
+        gcm::check_tag_length(tag_length)?;
+        gcm::check_nonce(nonce)?;
+        let mode = &"encrypt".to_string();
+
+        let auth_key = aes_sea_128::execute(&algorithm, mode, &key, vec![0; 16])?;
+        let y0 = gcm::initial_counter(nonce, &auth_key);
+        let y0_encrypted = aes_sea_128::execute(&algorithm, mode, &key, y0.clone())?;
+
+        Ok(GcmStream {
+            algorithm,
+            key,
+            auth_key,
+            y0,
+            y0_encrypted,
+            tag_length,
+            q: vec![0; 16],
+            pending: Vec::new(),
+            keystream: Vec::new(),
+            counter: 0,
+            ad_length: 0,
+            text_length: 0,
+            ad_finished: false
+        })
+    }
+
+    // Feed bytes into GHASH, only complete blocks are processed
+    fn absorb(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+        let complete = self.pending.len() - self.pending.len() % 16;
+        for chunk in self.pending[..complete].chunks(16) {
+            self.q = gcm::ghash_round(self.q.clone(), chunk, &self.auth_key);
+        }
+        self.pending.drain(..complete);
+    }
+
+    // Pad the last partial block with zeros, GHASH round does that for a short chunk
+    fn flush(&mut self) {
+        if !self.pending.is_empty() {
+            self.q = gcm::ghash_round(self.q.clone(), &self.pending, &self.auth_key);
+            self.pending.clear();
+        }
+    }
+
+    fn update_aad(&mut self, ad: &[u8]) -> Result<(), String> {
+        if self.ad_finished {
+            return Err("Associated data has to be given before the first update".to_string());
+        }
+        self.ad_length += ad.len() as u64;
+        self.absorb(ad);
+        Ok(())
+    }
+
+    fn finish_ad(&mut self) {
+        if !self.ad_finished {
+            self.flush();
+            self.ad_finished = true;
+        }
+    }
+
+    // Counts the text towards the length block, refuses it if it exceeds the limit
+    fn add_text_length(&mut self, length: usize) -> Result<(), String> {
+        let text_length = self.text_length + length as u64;
+        if text_length.div_ceil(16) > MAX_BLOCKS {
+            return Err(format!("Text exceeds the GCM limit of {} blocks", MAX_BLOCKS));
+        }
+        self.text_length = text_length;
+        Ok(())
+    }
+
+    // En- or decrypt with the key stream, GHASH always runs over the ciphertext side
+    fn process(&mut self, input: &[u8], encrypting: bool) -> Result<Vec<u8>, String> {
+        self.finish_ad();
+        self.add_text_length(input.len())?;
+
+        let mut output = Vec::with_capacity(input.len());
+        for &byte in input {
+            if self.keystream.is_empty() {
+                self.counter += 1; // Cannot overflow, add_text_length enforces the block limit
+                let y = gcm::increment_counter(&self.y0, self.counter);
+                self.keystream = aes_sea_128::execute(&self.algorithm, &"encrypt".to_string(), &self.key, y)?;
+                self.keystream.reverse(); // Pop the key stream from the back
+            }
+            output.push(byte ^ self.keystream.pop().unwrap());
+        }
+
+        self.absorb(if encrypting { &output } else { input });
+        Ok(output)
+    }
+
+    // The full tag, the length block L and H
+    fn finish(mut self) -> StreamOutput {
+        self.flush();
+
+        let l: Vec<u8> = (self.ad_length * 8).to_be_bytes().iter().chain((self.text_length * 8).to_be_bytes().iter()).cloned().collect();
+        let mut q = gcm::ghash_round(self.q, &l, &self.auth_key);
+
+        // XOR bytewise of Q and Y0
+        for (q, y) in q.iter_mut().zip(&self.y0_encrypted) {
+            *q ^= y;
+        }
+        (q, l, self.auth_key)
+    }
+}
+
+pub struct GcmEncryptor {
+    stream: GcmStream
+}
+
+impl GcmEncryptor {
+    pub fn new(algorithm: String, key: Vec<u8>, nonce: &[u8], tag_length: usize) -> Result<Self, String> {
+        Ok(GcmEncryptor { stream: GcmStream::new(algorithm, key, nonce, tag_length)? })
+    }
+
+    pub fn update_aad(&mut self, ad: &[u8]) -> Result<(), String> {
+        self.stream.update_aad(ad)
+    }
+
+    pub fn update(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        self.stream.process(plaintext, true)
+    }
+
+    pub fn finalize(self) -> StreamOutput {
+        let tag_length = self.stream.tag_length;
+        let (mut tag, l, h) = self.stream.finish();
+        tag.truncate(tag_length);
+        (tag, l, h)
+    }
+}
+
+// The plaintext of the update calls is not authentic until finalize accepted the tag
+pub struct GcmDecryptor {
+    stream: GcmStream
+}
+
+impl GcmDecryptor {
+    pub fn new(algorithm: String, key: Vec<u8>, nonce: &[u8], tag_length: usize) -> Result<Self, String> {
+        Ok(GcmDecryptor { stream: GcmStream::new(algorithm, key, nonce, tag_length)? })
+    }
+
+    pub fn update_aad(&mut self, ad: &[u8]) -> Result<(), String> {
+        self.stream.update_aad(ad)
+    }
+
+    pub fn update(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+        self.stream.process(ciphertext, false)
+    }
+
+    // Only authenticates the ciphertext, for a first pass that releases no plaintext
+    pub fn authenticate(&mut self, ciphertext: &[u8]) -> Result<(), String> {
+        self.stream.finish_ad();
+        self.stream.add_text_length(ciphertext.len())?;
+        self.stream.absorb(ciphertext);
+        Ok(())
+    }
+
+    pub fn finalize(self, tag: &[u8]) -> Result<bool, String> {
+        let tag_length = self.stream.tag_length;
+        if tag.len() != tag_length {
+            return Err(format!("Tag has {} bytes, expected {}", tag.len(), tag_length));
+        }
+
+        let (expected, _, _) = self.stream.finish();
+        Ok(openssl::memcmp::eq(&expected[..tag_length], tag)) // Compare in constant time
+    }
+}
+
+// Reads 'input' chunk by chunk, the whole file is never held in memory
+fn read_chunks(input: &str, mut consume: impl FnMut(&[u8]) -> Result<(), String>) -> Result<(), String> {
+    let mut reader = File::open(input).map_err(|e| format!("Failed to open {}: {}", input, e))?;
+
+    let mut buffer = vec![0u8; FILE_CHUNK_SIZE];
+    loop {
+        let count = reader.read(&mut buffer).map_err(|e| format!("Failed to read {}: {}", input, e))?;
+        if count == 0 {
+            return Ok(());
+        }
+        consume(&buffer[..count])?;
+    }
+}
+
+fn stream_file(input: &str, output: &str, mut update: impl FnMut(&[u8]) -> Result<Vec<u8>, String>) -> Result<(), String> {
+    let mut writer = File::create(output).map_err(|e| format!("Failed to create {}: {}", output, e))?;
+    read_chunks(input, |chunk| writer.write_all(&update(chunk)?).map_err(|e| format!("Failed to write {}: {}", output, e)))
+}
+
+pub fn encrypt_file(algorithm: String, key: Vec<u8>, nonce: &[u8], ad: &[u8], tag_length: usize, input: &str, output: &str) -> Result<StreamOutput, String> {
+    let mut encryptor = GcmEncryptor::new(algorithm, key, nonce, tag_length)?;
+    encryptor.update_aad(ad)?;
+
+    stream_file(input, output, |chunk| encryptor.update(chunk))?;
+    Ok(encryptor.finalize())
+}
+
+// The tag is checked in a first pass over the file, the plaintext is only written once it matched.
+// The second pass checks it again in case the file changed in between and writes to a temporary file,
+// which only replaces the output if both checks matched
+pub fn decrypt_file(algorithm: String, key: Vec<u8>, nonce: &[u8], ad: &[u8], tag: &[u8], input: &str, output: &str) -> Result<bool, String> {
+    let mut verifier = GcmDecryptor::new(algorithm.clone(), key.clone(), nonce, tag.len())?;
+    verifier.update_aad(ad)?;
+    read_chunks(input, |chunk| verifier.authenticate(chunk))?;
+    if !verifier.finalize(tag)? {
+        return Ok(false);
+    }
+
+    let mut decryptor = GcmDecryptor::new(algorithm, key, nonce, tag.len())?;
+    decryptor.update_aad(ad)?;
+    let temporary = format!("{}.part", output);
+    let authentic = stream_file(input, &temporary, |chunk| decryptor.update(chunk)).and_then(|_| decryptor.finalize(tag));
+
+    match authentic {
+        Ok(true) => fs::rename(&temporary, output).map_err(|e| format!("Failed to rename {} to {}: {}", temporary, output, e))?,
+        _ => {
+            let _ = fs::remove_file(&temporary);
+        }
+    }
+    authentic
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::de_encode_base64::decode_str;
+    use std::fs;
+
+    fn test_case_16() -> (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>) {
+        let nonce = decode_str("yv66vvrO263eyviI");
+        let key = decode_str("/v/pkoZlcxxtao+UZzCDCP7/6ZKGZXMcbWqPlGcwgwg=");
+        let plaintext = decode_str("2TEyJfiEBuWlWQnFr/UmmoanqVMVNPfaLkwwPYoxinIcPAyVlWgJUy/PDiRJprUlsWrt9aoN5le6Y3s5");
+        let ad = decode_str("/u36zt6tvu/+7frO3q2+76ut2tI=");
+        (nonce, key, plaintext, ad)
+    }
+
+    #[test]
+    fn matches_one_shot_for_any_split() {
+        let (nonce, key, plaintext, ad) = test_case_16();
+        let expected = gcm::encrypt("aes256".to_string(), nonce.clone(), key.clone(), plaintext.clone(), ad.clone(), 16).unwrap();
+
+        for split in [1, 5, 16, 17, 33, 60] {
+            let mut encryptor = GcmEncryptor::new("aes256".to_string(), key.clone(), &nonce, 16).unwrap();
+            for chunk in ad.chunks(3) {
+                encryptor.update_aad(chunk).unwrap();
+            }
+
+            let mut ciphertext = Vec::new();
+            for chunk in plaintext.chunks(split) {
+                ciphertext.extend(encryptor.update(chunk).unwrap());
+            }
+            let (tag, l, h) = encryptor.finalize();
+            assert_eq!((ciphertext, tag, l, h), expected.clone());
+        }
+    }
+
+    #[test]
+    fn decryptor_roundtrip() {
+        let (nonce, key, plaintext, ad) = test_case_16();
+        let (ciphertext, tag, _, _) = gcm::encrypt("aes256".to_string(), nonce.clone(), key.clone(), plaintext.clone(), ad.clone(), 12).unwrap();
+
+        let mut decryptor = GcmDecryptor::new("aes256".to_string(), key.clone(), &nonce, 12).unwrap();
+        decryptor.update_aad(&ad).unwrap();
+        let mut decrypted = Vec::new();
+        for chunk in ciphertext.chunks(7) {
+            decrypted.extend(decryptor.update(chunk).unwrap());
+        }
+        assert_eq!(decrypted, plaintext);
+        assert_eq!(decryptor.finalize(&tag), Ok(true));
+
+        // Associated data after the first update is refused, a wrong tag is not authentic
+        let mut decryptor = GcmDecryptor::new("aes256".to_string(), key, &nonce, 12).unwrap();
+        decryptor.update(&ciphertext).unwrap();
+        assert!(decryptor.update_aad(&ad).is_err());
+        assert_eq!(decryptor.finalize(&tag), Ok(false));
+    }
+
+    #[test]
+    fn block_limit() {
+        let (nonce, key, _, _) = test_case_16();
+        let mut encryptor = GcmEncryptor::new("aes256".to_string(), key, &nonce, 16).unwrap();
+
+        // Pretend all but the last allowed block have been processed
+        encryptor.stream.counter = (MAX_BLOCKS - 1) as u32;
+        encryptor.stream.text_length = (MAX_BLOCKS - 1) * 16;
+
+        assert!(encryptor.update(&[0u8; 16]).is_ok());
+        assert_eq!(encryptor.stream.counter, u32::MAX - 1);
+        assert!(encryptor.update(&[0u8; 1]).is_err());
+    }
+
+    #[test]
+    fn empty_nonce() {
+        let (_, key, _, _) = test_case_16();
+
+        assert!(GcmEncryptor::new("aes256".to_string(), key.clone(), &[], 16).is_err());
+        assert!(GcmDecryptor::new("aes256".to_string(), key, &[], 16).is_err());
+    }
+
+    #[test]
+    fn file_roundtrip() {
+        let (nonce, key, plaintext, ad) = test_case_16();
+        let directory = std::env::temp_dir().join(format!("kauma-gcm-file-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = |name: &str| directory.join(name).to_str().unwrap().to_string();
+
+        // Large enough to span several read chunks
+        let content: Vec<u8> = plaintext.iter().cycle().take(3 * FILE_CHUNK_SIZE + 7).cloned().collect();
+        fs::write(path("plain"), &content).unwrap();
+
+        let (tag, l, h) = encrypt_file("aes256".to_string(), key.clone(), &nonce, &ad, 16, &path("plain"), &path("cipher")).unwrap();
+        let expected = gcm::encrypt("aes256".to_string(), nonce.clone(), key.clone(), content.clone(), ad.clone(), 16).unwrap();
+        assert_eq!((fs::read(path("cipher")).unwrap(), tag.clone(), l, h), expected);
+
+        assert_eq!(decrypt_file("aes256".to_string(), key.clone(), &nonce, &ad, &tag, &path("cipher"), &path("decrypted")), Ok(true));
+        assert_eq!(fs::read(path("decrypted")).unwrap(), content);
+        assert!(!directory.join("decrypted.part").exists());
+
+        // A forged tag leaves no plaintext behind
+        let mut forged = tag;
+        forged[0] ^= 1;
+        assert_eq!(decrypt_file("aes256".to_string(), key.clone(), &nonce, &ad, &forged, &path("cipher"), &path("forged")), Ok(false));
+        assert!(!directory.join("forged").exists());
+
+        // So does a file that cannot be read
+        assert!(decrypt_file("aes256".to_string(), key.clone(), &nonce, &ad, &forged, &path("missing"), &path("missing-out")).is_err());
+        assert!(!directory.join("missing-out").exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
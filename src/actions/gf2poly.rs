@@ -0,0 +1,494 @@
+// A polynomial over GF(2) packed into 64 bit words, bit i of word j is the coefficient of
+// x^(64j + i). The zero polynomial has no words and there are never trailing zero words.
+pub type Gf2Poly = Vec<u64>;
+
+type Gf2Factorization = Vec<(Gf2Poly, u32)>;
+
+fn normalize(mut f: Gf2Poly) -> Gf2Poly {
+    while f.last() == Some(&0) {
+        f.pop();
+    }
+    f
+}
+
+pub fn from_exponents(exponents: &[u32]) -> Gf2Poly {
+    let words = exponents.iter().max().map_or(0, |&e| e as usize / 64 + 1);
+    let mut f = vec![0u64; words];
+    for &e in exponents {
+        f[e as usize / 64] ^= 1 << (e % 64); // Repeated exponents cancel
+    }
+    normalize(f)
+}
+
+// Exponents of the non-zero coefficients, highest first
+pub fn to_exponents(f: &[u64]) -> Vec<u32> {
+    let mut exponents = Vec::new();
+    for (j, &word) in f.iter().enumerate().rev() {
+        for i in (0..64).rev() {
+            if (word >> i) & 1 == 1 {
+                exponents.push(64 * j as u32 + i);
+            }
+        }
+    }
+    exponents
+}
+
+pub fn degree(f: &[u64]) -> isize {
+    match f.last() {
+        Some(&word) => 64 * (f.len() as isize - 1) + 63 - word.leading_zeros() as isize,
+        None => -1
+    }
+}
+
+fn one() -> Gf2Poly {
+    vec![1]
+}
+
+fn x() -> Gf2Poly {
+    vec![2]
+}
+
+pub fn add(a: &[u64], b: &[u64]) -> Gf2Poly {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = long.to_vec();
+    for (word, &other) in sum.iter_mut().zip(short) {
+        *word ^= other;
+    }
+    normalize(sum)
+}
+
+fn shift_left(f: &[u64], k: usize) -> Gf2Poly {
+    if f.is_empty() {
+        return Vec::new();
+    }
+    let (words, bits) = (k / 64, k % 64);
+    let mut shifted = vec![0u64; f.len() + words + 1];
+    for (j, &word) in f.iter().enumerate() {
+        shifted[j + words] |= word << bits;
+        if bits > 0 {
+            shifted[j + words + 1] |= word >> (64 - bits);
+        }
+    }
+    normalize(shifted)
+}
+
+// Carry-less product of two words
+fn clmul(a: u64, b: u64) -> u128 {
+    let mut product: u128 = 0;
+    for i in (0..64).filter(|i| (b >> i) & 1 == 1) {
+        product ^= (a as u128) << i;
+    }
+    product
+}
+
+pub fn mul(a: &[u64], b: &[u64]) -> Gf2Poly {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut product = vec![0u64; a.len() + b.len()];
+    for (i, &a_word) in a.iter().enumerate() {
+        for (j, &b_word) in b.iter().enumerate() {
+            let partial = clmul(a_word, b_word);
+            product[i + j] ^= partial as u64;
+            product[i + j + 1] ^= (partial >> 64) as u64;
+        }
+    }
+    normalize(product)
+}
+
+pub fn divmod(a: &[u64], b: &[u64]) -> (Gf2Poly, Gf2Poly) {
+    let b_degree = degree(b);
+    assert!(b_degree >= 0, "Division by zero polynomial");
+
+    let mut remainder = a.to_vec();
+    let mut quotient = vec![0u64; a.len()];
+    while degree(&remainder) >= b_degree {
+        let shift = (degree(&remainder) - b_degree) as usize;
+        quotient[shift / 64] |= 1 << (shift % 64);
+        remainder = add(&remainder, &shift_left(b, shift));
+    }
+    (normalize(quotient), remainder)
+}
+
+fn rem(a: &[u64], m: &[u64]) -> Gf2Poly {
+    divmod(a, m).1
+}
+
+fn mulmod(a: &[u64], b: &[u64], m: &[u64]) -> Gf2Poly {
+    rem(&mul(a, b), m)
+}
+
+pub fn gcd(a: &[u64], b: &[u64]) -> Gf2Poly {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    while !b.is_empty() {
+        let r = rem(&a, &b);
+        a = b;
+        b = r;
+    }
+    a
+}
+
+// h^(2^k) mod m
+fn square_times(h: &[u64], k: usize, m: &[u64]) -> Gf2Poly {
+    let mut h = rem(h, m);
+    for _ in 0..k {
+        h = mulmod(&h, &h, m);
+    }
+    h
+}
+
+fn powmod(h: &[u64], mut e: u128, m: &[u64]) -> Gf2Poly {
+    let mut result = rem(&one(), m);
+    let mut base = rem(h, m);
+    while e > 0 {
+        if e & 1 == 1 {
+            result = mulmod(&result, &base, m);
+        }
+        base = mulmod(&base, &base, m);
+        e >>= 1;
+    }
+    result
+}
+
+// In characteristic 2 the derivative keeps the odd exponents, each lowered by one
+pub fn diff(f: &[u64]) -> Gf2Poly {
+    let mut derivative = vec![0u64; f.len()];
+    for (j, &word) in f.iter().enumerate() {
+        derivative[j] = (word >> 1) & 0x5555_5555_5555_5555;
+        if let Some(&next) = f.get(j + 1) {
+            derivative[j] |= (next << 63) & 0x5555_5555_5555_5555;
+        }
+    }
+    normalize(derivative)
+}
+
+// Square root of a polynomial with only even exponents
+fn sqrt(f: &[u64]) -> Gf2Poly {
+    let exponents: Vec<u32> = to_exponents(f).iter().map(|e| e / 2).collect();
+    from_exponents(&exponents)
+}
+
+pub fn factor_sff(f: &[u64]) -> Gf2Factorization {
+    let mut c = gcd(f, &diff(f));
+    let mut w = divmod(f, &c).0;
+    let mut factors = Vec::new();
+    let mut exponent = 1;
+
+    while w != one() {
+        let y = gcd(&w, &c);
+        let z = divmod(&w, &y).0;
+        if z != one() {
+            factors.push((z, exponent));
+        }
+        exponent += 1;
+        w = y.clone();
+        c = divmod(&c, &y).0;
+    }
+
+    // What is left is a square
+    if c != one() {
+        for (g, e) in factor_sff(&sqrt(&c)) {
+            factors.push((g, 2 * e));
+        }
+    }
+    factors
+}
+
+// Groups the irreducible factors of a square-free polynomial by their degree
+pub fn factor_ddf(f: &[u64]) -> Vec<(Gf2Poly, usize)> {
+    let mut rest = f.to_vec();
+    let mut h = rem(&x(), &rest);
+    let mut groups = Vec::new();
+    let mut d = 1;
+
+    while degree(&rest) >= 2 * d as isize {
+        h = mulmod(&h, &h, &rest);
+        let g = gcd(&add(&h, &x()), &rest);
+        if g != one() {
+            rest = divmod(&rest, &g).0;
+            h = rem(&h, &rest);
+            groups.push((g, d));
+        }
+        d += 1;
+    }
+    if degree(&rest) > 0 {
+        let d = degree(&rest) as usize;
+        groups.push((rest, d));
+    }
+    groups
+}
+
+// Splits a product of irreducible factors of degree d. The trace Tr(a) = a + a^2 + ... + a^(2^(d-1))
+// is 0 or 1 modulo every factor and the traces of the basis elements x^j separate all of them
+pub fn factor_edf(f: &[u64], d: usize) -> Vec<Gf2Poly> {
+    let n = degree(f) as usize;
+    let count = n / d;
+    let mut factors = vec![f.to_vec()];
+
+    for j in 1..n {
+        if factors.len() == count {
+            break;
+        }
+
+        let mut a = shift_left(&one(), j);
+        let mut trace = Vec::new();
+        for _ in 0..d {
+            a = rem(&a, f);
+            trace = add(&trace, &a);
+            a = mulmod(&a, &a, f);
+        }
+
+        let mut new_factors = Vec::new();
+        for u in factors {
+            let g = gcd(&u, &trace);
+            if degree(&g) > 0 && degree(&g) < degree(&u) {
+                new_factors.push(divmod(&u, &g).0);
+                new_factors.push(g);
+            } else {
+                new_factors.push(u);
+            }
+        }
+        factors = new_factors;
+    }
+    factors
+}
+
+// Irreducible factors with their exponents, ordered by degree and then by value
+pub fn factor(f: &[u64]) -> Gf2Factorization {
+    let mut factors = Vec::new();
+    if degree(f) < 1 {
+        return factors;
+    }
+
+    for (square_free, exponent) in factor_sff(f) {
+        for (group, d) in factor_ddf(&square_free) {
+            for irreducible in factor_edf(&group, d) {
+                factors.push((irreducible, exponent));
+            }
+        }
+    }
+    factors.sort_by(|(a, _), (b, _)| {
+        degree(a).cmp(&degree(b)).then_with(|| to_exponents(a).cmp(&to_exponents(b)))
+    });
+    factors
+}
+
+// Rabin's test: x^(2^n) = x mod f and gcd(x^(2^(n/p)) - x, f) = 1 for every prime p dividing n
+pub fn is_irreducible(f: &[u64]) -> bool {
+    let n = degree(f);
+    if n < 1 {
+        return false;
+    }
+    let n = n as usize;
+
+    if square_times(&x(), n, f) != rem(&x(), f) {
+        return false;
+    }
+    prime_divisors(n as u128)
+        .into_iter()
+        .all(|p| gcd(&add(&square_times(&x(), n / p as usize, f), &x()), f) == one())
+}
+
+// x generates the multiplicative group of GF(2)[x]/f, so its order is 2^n - 1
+pub fn is_primitive(f: &[u64]) -> Result<bool, String> {
+    let n = degree(f);
+    if n > 128 {
+        return Err("Primitivity is only decided up to degree 128".to_string());
+    }
+    if !is_irreducible(f) || rem(&x(), f).is_empty() {
+        return Ok(false);
+    }
+
+    let order = u128::MAX >> (128 - n);
+    Ok(prime_divisors(order)
+        .into_iter()
+        .all(|p| powmod(&x(), order / p, f) != one()))
+}
+
+fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    if m <= u64::MAX as u128 {
+        return (a % m) * (b % m) % m;
+    }
+
+    // Double and add, the sums are kept below m so nothing overflows
+    let add_mod = |x: u128, y: u128| if x >= m - y { x - (m - y) } else { x + y };
+    let (mut a, mut b) = (a % m, b % m);
+    let mut product = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            product = add_mod(product, a);
+        }
+        a = add_mod(a, a);
+        b >>= 1;
+    }
+    product
+}
+
+fn pow_mod(mut base: u128, mut e: u128, m: u128) -> u128 {
+    let mut result = 1 % m;
+    base %= m;
+    while e > 0 {
+        if e & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        e >>= 1;
+    }
+    result
+}
+
+// Miller-Rabin with the first prime bases, the smaller factors are already removed by trial division
+fn is_prime(n: u128) -> bool {
+    const BASES: [u128; 16] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+    if n < 2 {
+        return false;
+    }
+    if let Some(&p) = BASES.iter().find(|&&p| n.is_multiple_of(p)) {
+        return n == p;
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    BASES.iter().all(|&a| {
+        let mut y = pow_mod(a, d, n);
+        if y == 1 || y == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            y = mul_mod(y, y, n);
+            if y == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// Pollard's rho with Floyd cycle detection, returns a non-trivial divisor of a composite n
+fn pollard_rho(n: u128) -> u128 {
+    for c in 1.. {
+        let step = |x: u128| {
+            let y = mul_mod(x, x, n);
+            if y >= n - c { y - (n - c) } else { y + c }
+        };
+        let (mut slow, mut fast) = (2u128, 2u128);
+        loop {
+            slow = step(slow);
+            fast = step(step(fast));
+            let d = gcd_u128(slow.abs_diff(fast), n);
+            if d == n {
+                break; // Cycle without a factor, try another constant
+            }
+            if d > 1 {
+                return d;
+            }
+        }
+    }
+    unreachable!()
+}
+
+fn prime_divisors(mut n: u128) -> Vec<u128> {
+    let mut primes = Vec::new();
+    let mut p = 2;
+    while p * p <= n && p < 1 << 16 {
+        if n.is_multiple_of(p) {
+            primes.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+        p += 1;
+    }
+
+    // The cofactor has no divisors below 2^16, split it further if it is composite
+    let mut rest = vec![n];
+    while let Some(m) = rest.pop() {
+        if m == 1 {
+            continue;
+        }
+        if is_prime(m) {
+            primes.push(m);
+        } else {
+            let d = pollard_rho(m);
+            rest.push(d);
+            rest.push(m / d);
+        }
+    }
+    primes.sort();
+    primes.dedup();
+    primes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcm_reduction_polynomial_is_primitive() {
+        // The constant 0x87 in gfmul_f128 stands for x^128 + x^7 + x^2 + x + 1
+        let f = from_exponents(&[128, 7, 2, 1, 0]);
+        assert!(is_irreducible(&f));
+        assert_eq!(is_primitive(&f), Ok(true));
+    }
+
+    #[test]
+    fn irreducible_but_not_primitive() {
+        // x^4 + x^3 + x^2 + x + 1 divides x^5 - 1, so x has order 5 instead of 15
+        let f = from_exponents(&[4, 3, 2, 1, 0]);
+        assert!(is_irreducible(&f));
+        assert_eq!(is_primitive(&f), Ok(false));
+        assert_eq!(is_primitive(&from_exponents(&[4, 1, 0])), Ok(true));
+        assert!(!is_irreducible(&from_exponents(&[4, 2, 0])));
+    }
+
+    #[test]
+    fn mul_and_divmod_across_words() {
+        let a = from_exponents(&[130, 64, 63, 1]);
+        let b = from_exponents(&[70, 3, 0]);
+        let product = mul(&a, &b);
+
+        assert_eq!(degree(&product), 200);
+        assert_eq!(divmod(&product, &b), (a.clone(), Vec::new()));
+        assert_eq!(mul(&from_exponents(&[1, 0]), &from_exponents(&[1, 0])), from_exponents(&[2, 0]));
+        assert_eq!(diff(&from_exponents(&[65, 64, 3])), from_exponents(&[64, 2]));
+    }
+
+    #[test]
+    fn factor_recovers_product() {
+        // (x + 1)^3 * (x^2 + x + 1) * (x^3 + x + 1) * (x^3 + x^2 + 1)^2 * (x^64 + x^4 + x^3 + x + 1)
+        let parts = [
+            (from_exponents(&[1, 0]), 3),
+            (from_exponents(&[2, 1, 0]), 1),
+            (from_exponents(&[3, 1, 0]), 1),
+            (from_exponents(&[3, 2, 0]), 2),
+            (from_exponents(&[64, 4, 3, 1, 0]), 1),
+        ];
+        let mut f = one();
+        for (g, e) in &parts {
+            for _ in 0..*e {
+                f = mul(&f, g);
+            }
+        }
+
+        assert!(parts.iter().all(|(g, _)| is_irreducible(g)));
+        assert_eq!(factor(&f), parts.to_vec());
+    }
+
+    #[test]
+    fn prime_divisors_of_mersenne_numbers() {
+        assert_eq!(prime_divisors(u128::MAX >> 64), vec![3, 5, 17, 257, 641, 65537, 6700417]);
+        assert_eq!(prime_divisors((1 << 61) - 1), vec![(1 << 61) - 1]);
+        assert_eq!(
+            prime_divisors(u128::MAX),
+            vec![3, 5, 17, 257, 641, 65537, 274177, 6700417, 67280421310721]
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use super::gf_operations;
+
+// A matrix over GF(2^128) is a list of rows, every entry a 16 byte block in gcm semantic
+pub type Matrix = Vec<Vec<Vec<u8>>>;
+
+fn zero() -> Vec<u8> {
+    vec![0u8; 16]
+}
+
+fn one() -> Vec<u8> {
+    let mut block = vec![0u8; 16];
+    block[0] = 0x80;
+    block
+}
+
+fn is_zero(block: &[u8]) -> bool {
+    block.iter().all(|&x| x == 0)
+}
+
+fn mul(a: &[u8], b: &[u8]) -> Vec<u8> {
+    gf_operations::gfmul(&"gcm".to_string(), a.to_vec(), b.to_vec())
+}
+
+fn dimensions(matrix: &[Vec<Vec<u8>>]) -> Result<(usize, usize), String> {
+    let rows = matrix.len();
+    let columns = matrix.first().map_or(0, |row| row.len());
+    if rows == 0 || columns == 0 {
+        return Err("Matrix is empty".to_string());
+    }
+    if matrix.iter().any(|row| row.len() != columns) {
+        return Err("Rows of the matrix differ in length".to_string());
+    }
+    if matrix.iter().flatten().any(|entry| entry.len() != 16) {
+        return Err("Matrix entries must be 16 byte blocks".to_string());
+    }
+    Ok((rows, columns))
+}
+
+pub fn identity(n: usize) -> Matrix {
+    let mut matrix = vec![vec![zero(); n]; n];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        row[i] = one();
+    }
+    matrix
+}
+
+// Reduced row echelon form, returns the reduced matrix, the pivot columns and the product
+// of the pivots before normalization (row swaps do not change the sign in characteristic 2)
+fn eliminate(mut matrix: Matrix, columns: usize) -> (Matrix, Vec<usize>, Vec<u8>) {
+    let rows = matrix.len();
+    let mut pivots: Vec<usize> = Vec::new();
+    let mut pivot_product = one();
+
+    for column in 0..columns {
+        let row = pivots.len();
+        if row == rows {
+            break;
+        }
+        let Some(pivot) = (row..rows).find(|&i| !is_zero(&matrix[i][column])) else {
+            continue;
+        };
+        matrix.swap(row, pivot);
+
+        pivot_product = mul(&pivot_product, &matrix[row][column]);
+        let inverse = gf_operations::gfdiv(one(), matrix[row][column].clone());
+        for entry in matrix[row].iter_mut() {
+            *entry = mul(entry, &inverse);
+        }
+
+        // Eliminate the column in every other row
+        let pivot_row = matrix[row].clone();
+        for (i, current) in matrix.iter_mut().enumerate() {
+            if i != row && !is_zero(&current[column]) {
+                let factor = current[column].clone();
+                for (entry, pivot_entry) in current.iter_mut().zip(&pivot_row) {
+                    *entry = gf_operations::add_vec(entry, &mul(&factor, pivot_entry));
+                }
+            }
+        }
+        pivots.push(column);
+    }
+    (matrix, pivots, pivot_product)
+}
+
+pub fn rref(matrix: &[Vec<Vec<u8>>]) -> Result<(Matrix, Vec<usize>), String> {
+    let (_, columns) = dimensions(matrix)?;
+    let (reduced, pivots, _) = eliminate(matrix.to_vec(), columns);
+    Ok((reduced, pivots))
+}
+
+pub fn rank(matrix: &[Vec<Vec<u8>>]) -> Result<usize, String> {
+    Ok(rref(matrix)?.1.len())
+}
+
+pub fn determinant(matrix: &[Vec<Vec<u8>>]) -> Result<Vec<u8>, String> {
+    let (rows, columns) = dimensions(matrix)?;
+    if rows != columns {
+        return Err("Determinant requires a square matrix".to_string());
+    }
+
+    let (_, pivots, pivot_product) = eliminate(matrix.to_vec(), columns);
+    if pivots.len() < rows {
+        return Ok(zero());
+    }
+    Ok(pivot_product)
+}
+
+pub fn inverse(matrix: &[Vec<Vec<u8>>]) -> Result<Matrix, String> {
+    let (rows, columns) = dimensions(matrix)?;
+    if rows != columns {
+        return Err("Inverse requires a square matrix".to_string());
+    }
+
+    // Reduce [A | I] to [I | A^-1]
+    let augmented: Matrix = matrix
+        .iter()
+        .zip(identity(rows))
+        .map(|(row, identity_row)| row.iter().cloned().chain(identity_row).collect())
+        .collect();
+    let (reduced, pivots, _) = eliminate(augmented, columns);
+    if pivots.len() < rows || pivots[rows - 1] >= columns {
+        return Err("Matrix is singular".to_string());
+    }
+
+    Ok(reduced.into_iter().map(|row| row[columns..].to_vec()).collect())
+}
+
+// One solution x of A * x = b, any other solution differs by an element of the nullspace
+pub fn solve(matrix: &[Vec<Vec<u8>>], b: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, String> {
+    let (rows, columns) = dimensions(matrix)?;
+    if b.len() != rows {
+        return Err(format!("Right-hand side has {} entries, expected {}", b.len(), rows));
+    }
+    if b.iter().any(|entry| entry.len() != 16) {
+        return Err("Right-hand side entries must be 16 byte blocks".to_string());
+    }
+
+    let augmented: Matrix = matrix
+        .iter()
+        .zip(b)
+        .map(|(row, entry)| row.iter().cloned().chain([entry.clone()]).collect())
+        .collect();
+    let (reduced, pivots, _) = eliminate(augmented, columns + 1);
+    if pivots.last() == Some(&columns) {
+        return Err("System has no solution".to_string());
+    }
+
+    // Free variables are set to zero
+    let mut x = vec![zero(); columns];
+    for (row, &pivot) in pivots.iter().enumerate() {
+        x[pivot] = reduced[row][columns].clone();
+    }
+    Ok(x)
+}
+
+// Basis of the kernel {x : A * x = 0}, one vector per free column
+pub fn nullspace(matrix: &[Vec<Vec<u8>>]) -> Result<Matrix, String> {
+    let (_, columns) = dimensions(matrix)?;
+    let (reduced, pivots, _) = eliminate(matrix.to_vec(), columns);
+
+    // In characteristic 2 minus is plus, so the pivot entries can be copied directly
+    let mut basis = Vec::new();
+    for free in (0..columns).filter(|column| !pivots.contains(column)) {
+        let mut vector = vec![zero(); columns];
+        vector[free] = one();
+        for (row, &pivot) in pivots.iter().enumerate() {
+            vector[pivot] = reduced[row][free].clone();
+        }
+        basis.push(vector);
+    }
+    Ok(basis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn mul_vector(matrix: &[Vec<Vec<u8>>], x: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        matrix
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(x)
+                    .fold(zero(), |sum, (a, b)| gf_operations::add_vec(&sum, &mul(a, b)))
+            })
+            .collect()
+    }
+
+    fn mul_matrix(a: &[Vec<Vec<u8>>], b: &[Vec<Vec<u8>>]) -> Matrix {
+        let columns = b.first().map_or(0, |row| row.len());
+        a.iter()
+            .map(|row| {
+                (0..columns)
+                    .map(|j| {
+                        row.iter()
+                            .zip(b)
+                            .fold(zero(), |sum, (entry, b_row)| gf_operations::add_vec(&sum, &mul(entry, &b_row[j])))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn random_matrix(rng: &mut StdRng, rows: usize, columns: usize) -> Matrix {
+        (0..rows)
+            .map(|_| {
+                (0..columns)
+                    .map(|_| {
+                        let mut block = vec![0u8; 16];
+                        rng.fill(&mut block[..]);
+                        block
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn inverse_of_random_matrix() {
+        let mut rng = StdRng::seed_from_u64(38);
+        let a = random_matrix(&mut rng, 5, 5);
+
+        let a_inverse = inverse(&a).unwrap();
+        assert_eq!(mul_matrix(&a, &a_inverse), identity(5));
+        assert_eq!(mul_matrix(&a_inverse, &a), identity(5));
+    }
+
+    #[test]
+    fn determinant_is_multiplicative() {
+        let mut rng = StdRng::seed_from_u64(138);
+        let a = random_matrix(&mut rng, 4, 4);
+        let b = random_matrix(&mut rng, 4, 4);
+
+        let product = mul(&determinant(&a).unwrap(), &determinant(&b).unwrap());
+        assert_eq!(determinant(&mul_matrix(&a, &b)).unwrap(), product);
+        assert_eq!(determinant(&identity(3)).unwrap(), one());
+    }
+
+    #[test]
+    fn singular_matrix() {
+        let mut rng = StdRng::seed_from_u64(238);
+        let mut a = random_matrix(&mut rng, 4, 4);
+
+        // Make the last row the sum of the first two
+        a[3] = a[0].iter().zip(&a[1]).map(|(x, y)| gf_operations::add_vec(x, y)).collect();
+
+        assert_eq!(rank(&a).unwrap(), 3);
+        assert_eq!(determinant(&a).unwrap(), zero());
+        assert!(inverse(&a).is_err());
+
+        let kernel = nullspace(&a).unwrap();
+        assert_eq!(kernel.len(), 1);
+        assert_eq!(mul_vector(&a, &kernel[0]), vec![zero(); 4]);
+    }
+
+    #[test]
+    fn solve_underdetermined_system() {
+        let mut rng = StdRng::seed_from_u64(338);
+        let a = random_matrix(&mut rng, 3, 5);
+        let expected = random_matrix(&mut rng, 1, 5).remove(0);
+        let b = mul_vector(&a, &expected);
+
+        let x = solve(&a, &b).unwrap();
+        assert_eq!(mul_vector(&a, &x), b);
+        assert_eq!(nullspace(&a).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn inconsistent_system() {
+        let a = vec![vec![one(), one()], vec![one(), one()]];
+        let b = vec![one(), zero()];
+
+        assert!(solve(&a, &b).is_err());
+        assert!(dimensions(&[vec![one()], vec![]]).is_err());
+    }
+}
@@ -67,8 +67,7 @@ fn inverse_element_f(e: Vec<u8>, f: u32) -> Vec<u8> {
     let mut result: u128 = 1;
     let mut base = e.clone();
 
-    let exponent: u128 = 2;
-    let mut exponent: u128 = exponent.pow(f) - 2; // 2^f - 2
+    let mut exponent: u128 = (u128::MAX >> (128 - f)) - 1; // 2^f - 2, without overflowing for f = 128
     while exponent > 0 {
         // If k is odd, multiply result by base
         if exponent % 2 == 1 {
@@ -1,9 +1,10 @@
 use std::mem;
 use num::BigUint;
 use num::{One, Zero};
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
 
 use super::gf_operations::{self, gfmul};
+use super::gf_matrix;
 
 pub fn add(a: &Vec<Vec<u8>>, b: &Vec<Vec<u8>>) -> Vec<Vec<u8>> {
 
@@ -220,6 +221,191 @@ pub fn gcd(a: &Vec<Vec<u8>>, b: &Vec<Vec<u8>>) -> Vec<Vec<u8>> {
     make_monic(&a)
 }
 
+// Below this degree the half-GCD falls back to plain Euclidean steps
+const HGCD_THRESHOLD: isize = 16;
+
+// 2x2 matrix of polynomials, used to track the Euclidean steps in xgcd
+type PolyMatrix = [[Vec<Vec<u8>>; 2]; 2];
+
+// Monic gcd G together with the cofactors U and V with U*A + V*B = G
+type Bezout = (Vec<Vec<u8>>, Vec<Vec<u8>>, Vec<Vec<u8>>);
+
+// Leading coefficient together with the monic irreducible factors and their exponents
+type Factorization = (Vec<u8>, Vec<(Vec<Vec<u8>>, u128)>);
+
+// Combined residue R together with the product M of all moduli
+type CrtSolution = (Vec<Vec<u8>>, Vec<Vec<u8>>);
+
+pub fn xgcd(a: &[Vec<u8>], b: &[Vec<u8>]) -> Bezout {
+    let mut a = pop_last_zeros(a.to_vec());
+    let mut b = pop_last_zeros(b.to_vec());
+
+    // Bring the input in the form deg(a) > deg(b) and remember if we had to swap
+    let swapped = degree(&a) <= degree(&b);
+    if swapped {
+        mem::swap(&mut a, &mut b);
+    }
+
+    let (mut g, mut u, mut v) = if is_zero(&a) {
+        (zero(), zero(), zero())
+    } else if is_zero(&b) {
+        (a, one(), zero())
+    } else {
+        let m = gcd_matrix(&a, &b);
+        let (g, _) = apply_matrix(&m, &a, &b);
+        let [[u, v], _] = m;
+        (g, u, v)
+    };
+
+    // Make the gcd monic and scale the cofactors by the same factor
+    if !is_zero(&g) {
+        let lc_inverse = vec![gf_operations::gfdiv(one()[0].clone(), g.last().unwrap().clone())];
+        g = mul(&g, &lc_inverse);
+        u = mul(&u, &lc_inverse);
+        v = mul(&v, &lc_inverse);
+    }
+
+    if swapped {
+        mem::swap(&mut u, &mut v);
+    }
+    (g, u, v)
+}
+
+// Ok(None) if 'a' is not invertible, a zero modulus is an error
+pub fn invmod(a: &[Vec<u8>], m: &[Vec<u8>]) -> Result<Option<Vec<Vec<u8>>>, String> {
+    let m = pop_last_zeros(m.to_vec());
+    if is_zero(&m) {
+        return Err("Modulus is zero".to_string());
+    }
+    let (_, a) = divmod(&pop_last_zeros(a.to_vec()), &m);
+
+    // 'a' is only invertible if it is coprime to the modulus
+    let (g, u, _) = xgcd(&a, &m);
+    if g != one() {
+        return Ok(None);
+    }
+    let (_, inverse) = divmod(&u, &m);
+    Ok(Some(inverse))
+}
+
+// Returns the matrix M with M * (a, b) = (gcd, 0), requires deg(a) > deg(b) and b != 0
+fn gcd_matrix(a: &[Vec<u8>], b: &[Vec<u8>]) -> PolyMatrix {
+    let mut m = identity_matrix();
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+
+    while !is_zero(&b) {
+        // Jump over the first half of the remainder sequence at once
+        let h = hgcd(&a, &b);
+        (a, b) = apply_matrix(&h, &a, &b);
+        m = mul_matrix(&h, &m);
+        if is_zero(&b) {
+            break;
+        }
+
+        // Do one plain division step so every round makes progress
+        let (q, r) = divmod(&a, &b);
+        m = mul_matrix(&quotient_matrix(q), &m);
+        (a, b) = (b, r);
+    }
+    m
+}
+
+// Half-GCD (Thull-Yap): for deg(a) > deg(b) returns the matrix M with M * (a, b) = (c, d)
+// where c and d are consecutive remainders with deg(c) >= ceil(deg(a) / 2) > deg(d)
+fn hgcd(a: &[Vec<u8>], b: &[Vec<u8>]) -> PolyMatrix {
+    let m = (degree(a) + 1) / 2;
+    if degree(b) < m {
+        return identity_matrix();
+    }
+    if degree(a) < HGCD_THRESHOLD {
+        return hgcd_classic(a, b, m);
+    }
+
+    // Recurse on the upper halves, their quotients match those of 'a' and 'b'
+    let r = hgcd(&shift_down(a, m as usize), &shift_down(b, m as usize));
+    let (c, d) = apply_matrix(&r, a, b);
+    if degree(&d) < m {
+        return r;
+    }
+
+    let (q, e) = divmod(&c, &d);
+    let r = mul_matrix(&quotient_matrix(q), &r);
+    if degree(&e) < m {
+        return r;
+    }
+
+    let k = (2 * m - degree(&d)).max(0) as usize;
+    let s = hgcd(&shift_down(&d, k), &shift_down(&e, k));
+    mul_matrix(&s, &r)
+}
+
+fn hgcd_classic(a: &[Vec<u8>], b: &[Vec<u8>], m: isize) -> PolyMatrix {
+    let mut result = identity_matrix();
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+
+    while degree(&b) >= m {
+        let (q, r) = divmod(&a, &b);
+        result = mul_matrix(&quotient_matrix(q), &result);
+        (a, b) = (b, r);
+    }
+    result
+}
+
+// Matrix of a single Euclidean step (a, b) -> (b, a - q*b), in characteristic 2 minus is plus
+fn quotient_matrix(q: Vec<Vec<u8>>) -> PolyMatrix {
+    [[zero(), one()], [one(), q]]
+}
+
+fn identity_matrix() -> PolyMatrix {
+    [[one(), zero()], [zero(), one()]]
+}
+
+fn mul_matrix(x: &PolyMatrix, y: &PolyMatrix) -> PolyMatrix {
+    let entry = |i: usize, j: usize| add(&mul(&x[i][0], &y[0][j]), &mul(&x[i][1], &y[1][j]));
+    [[entry(0, 0), entry(0, 1)], [entry(1, 0), entry(1, 1)]]
+}
+
+fn apply_matrix(m: &PolyMatrix, a: &[Vec<u8>], b: &[Vec<u8>]) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let a = a.to_vec();
+    let b = b.to_vec();
+    (
+        add(&mul(&m[0][0], &a), &mul(&m[0][1], &b)),
+        add(&mul(&m[1][0], &a), &mul(&m[1][1], &b)),
+    )
+}
+
+// Divides by x^k and drops the remainder
+fn shift_down(a: &[Vec<u8>], k: usize) -> Vec<Vec<u8>> {
+    if a.len() <= k {
+        return zero();
+    }
+    pop_last_zeros(a[k..].to_vec())
+}
+
+// Degree of a polynomial, the zero polynomial has degree -1
+pub fn degree(a: &[Vec<u8>]) -> isize {
+    if is_zero(a) {
+        return -1;
+    }
+    a.len() as isize - 1
+}
+
+fn is_zero(a: &[Vec<u8>]) -> bool {
+    a.iter().all(|coefficient| coefficient.iter().all(|&x| x == 0))
+}
+
+fn zero() -> Vec<Vec<u8>> {
+    vec![vec![0u8; 16]]
+}
+
+fn one() -> Vec<Vec<u8>> {
+    let mut one_vect = vec![vec![0u8; 16]];
+    one_vect[0][0] = 0x80;
+    one_vect
+}
+
 pub fn sff(f: &Vec<Vec<u8>>) -> Vec<(Vec<Vec<u8>>, u128)> {
     let mut f = f.clone();
     let mut factor_found: Vec<(Vec<Vec<u8>>, u128)> = Vec::new();
@@ -296,6 +482,217 @@ pub fn ddf(f: &Vec<Vec<u8>>) -> Vec<(Vec<Vec<u8>>, u128)> {
     sort_tuples(z) 
 }
 
+pub fn ddf_bsgs(f: &[Vec<u8>]) -> Vec<(Vec<Vec<u8>>, u128)> {
+    let f = pop_last_zeros(f.to_vec());
+    let mut z: Vec<(Vec<Vec<u8>>, u128)> = Vec::new();
+    let n = degree(&f).max(0) as usize;
+
+    // Represent the polynomial 'x'
+    let mut x = vec![vec![0u8; 16]; 2];
+    x[1][0] = 0x80;
+
+    // Split the degrees 1..n/2 into m intervals of l baby steps each
+    let mut l = 1;
+    while 2 * l * l < n {
+        l += 1;
+    }
+
+    // Baby steps h_i = x^{q^i} mod f for i = 0..l, every step is a composition with x^q
+    let (_, x_mod_f) = divmod(&x, &f);
+    let mut baby = vec![x_mod_f];
+    if n > 0 {
+        baby.push(frobenius_power(&x, &f, 1));
+        for i in 2..=l {
+            let next = compose_mod(&baby[i - 1], &baby[1], &f).unwrap(); // n > 0, so f is not zero
+            baby.push(next);
+        }
+    }
+
+    let mut fstar = f.clone();
+    let mut giant = baby[l.min(baby.len() - 1)].clone();
+    let mut j = 1;
+
+    // Everything of degree <= l*(j-1) is gone, so a rest of degree < 2*(l*(j-1)+1) is irreducible
+    while degree(&fstar) >= 2 * (l * (j - 1) + 1) as isize {
+        // giant = x^{q^{l*j}} mod f, the interval product vanishes on all factors of degree l*(j-1) < d <= l*j
+        let mut interval = one();
+        for h in &baby[..l] {
+            (_, interval) = divmod(&mul(&interval, &add(&giant, h)), &f);
+        }
+
+        let mut g = gcd(&fstar, &interval);
+        if g != one() {
+            (fstar, _) = divmod(&fstar, &g);
+
+            // Separate the degrees inside the interval, smallest degree first
+            for i in (0..l).rev() {
+                let h = gcd(&g, &add(&giant, &baby[i]));
+                if h != one() {
+                    z.push((h.clone(), (l * j - i) as u128));
+                    (g, _) = divmod(&g, &h);
+                }
+            }
+        }
+
+        giant = compose_mod(&giant, &baby[l], &f).unwrap();
+        j += 1;
+    }
+
+    if fstar != one() {
+        z.push((fstar.clone(), (fstar.len() as u128 - 1)));
+    } else if z.is_empty() {
+        z.push((f.clone(), 1 ));
+    }
+    sort_tuples(z)
+}
+
+pub fn compose(f: &[Vec<u8>], g: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let g = pop_last_zeros(g.to_vec());
+
+    // Horner scheme f(g) = (...(f_n * g + f_{n-1}) * g + ...) + f_0
+    let mut result = zero();
+    for coefficient in pop_last_zeros(f.to_vec()).iter().rev() {
+        result = add(&mul(&result, &g), &vec![coefficient.clone()]);
+    }
+    result
+}
+
+// Brent-Kung modular composition f(g) mod m, a zero modulus is an error
+pub fn compose_mod(f: &[Vec<u8>], g: &[Vec<u8>], m: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, String> {
+    let f = pop_last_zeros(f.to_vec());
+    let m = pop_last_zeros(m.to_vec());
+    if is_zero(&m) {
+        return Err("Modulus is zero".to_string());
+    }
+    let (_, g) = divmod(&pop_last_zeros(g.to_vec()), &m);
+
+    // Chunk size k ~ sqrt(deg f), so f(y) = sum_i F_i(y) * (y^k)^i with deg F_i < k
+    let mut k = 1;
+    while k * k < f.len() {
+        k += 1;
+    }
+
+    // Baby powers g^0, ..., g^k mod m
+    let mut powers = vec![one()];
+    for t in 1..=k {
+        let (_, power) = divmod(&mul(&powers[t - 1], &g), &m);
+        powers.push(power);
+    }
+
+    // Horner in g^k over the chunks, starting with the highest one
+    let mut result = zero();
+    for chunk in f.chunks(k).rev() {
+        let mut chunk_value = zero();
+        for (coefficient, power) in chunk.iter().zip(&powers) {
+            chunk_value = add(&chunk_value, &mul(power, &vec![coefficient.clone()]));
+        }
+        (_, result) = divmod(&add(&mul(&result, &powers[k]), &chunk_value), &m);
+    }
+    Ok(result)
+}
+
+pub fn crt(residues: &[Vec<Vec<u8>>], moduli: &[Vec<Vec<u8>>]) -> Result<CrtSolution, String> {
+    if residues.len() != moduli.len() {
+        return Err(format!("Got {} residues but {} moduli", residues.len(), moduli.len()));
+    }
+
+    let mut result = zero();
+    let mut modulus = one();
+
+    for (i, (r, m)) in residues.iter().zip(moduli).enumerate() {
+        let m = pop_last_zeros(m.clone());
+        if is_zero(&m) {
+            return Err(format!("Modulus {} is zero", i));
+        }
+
+        // Name the first modulus that shares a factor with the current one
+        if gcd(&modulus, &m) != one() {
+            let j = moduli[..i].iter().position(|other| gcd(other, &m) != one()).unwrap_or(0);
+            return Err(format!("Moduli {} and {} are not coprime", j, i));
+        }
+
+        // Lift the result: result + modulus * ((r - result) * modulus^-1 mod m) is correct mod both
+        let inverse = invmod(&modulus, &m)?.unwrap();
+        let (_, t) = divmod(&mul(&add(r, &result), &inverse), &m);
+        result = add(&result, &mul(&modulus, &t));
+        modulus = mul(&modulus, &m);
+    }
+    Ok((result, modulus))
+}
+
+pub fn resultant(a: &[Vec<u8>], b: &[Vec<u8>]) -> Vec<u8> {
+    let semantic = "gcm".to_string();
+    let mut a = pop_last_zeros(a.to_vec());
+    let mut b = pop_last_zeros(b.to_vec());
+    let mut result = one()[0].clone();
+
+    if is_zero(&a) || is_zero(&b) {
+        return vec![0u8; 16];
+    }
+
+    // Euclidean algorithm: res(A, B) = lc(B)^(deg A - deg R) * res(B, R) with R = A mod B,
+    // all the signs (-1)^(deg A * deg B) vanish in characteristic 2
+    loop {
+        if degree(&b) == 0 {
+            let power = element_pow(&b[0], degree(&a) as u128);
+            return gf_operations::gfmul(&semantic, result, power);
+        }
+        if degree(&a) < degree(&b) {
+            mem::swap(&mut a, &mut b);
+            continue;
+        }
+
+        let (_, r) = divmod(&a, &b);
+        if is_zero(&r) {
+            return vec![0u8; 16]; // A and B share a factor
+        }
+
+        let power = element_pow(b.last().unwrap(), (degree(&a) - degree(&r)) as u128);
+        result = gf_operations::gfmul(&semantic, result, power);
+        (a, b) = (b, r);
+    }
+}
+
+pub fn discriminant(f: &[Vec<u8>]) -> Vec<u8> {
+    let f = pop_last_zeros(f.to_vec());
+    let n = degree(&f);
+    if n < 1 {
+        return vec![0u8; 16];
+    }
+
+    let derivative = diff(f.clone());
+    if is_zero(&derivative) {
+        return vec![0u8; 16]; // f is a square
+    }
+
+    // disc(f) = res(f, f') / lc(f), where f' counts with formal degree n - 1. In characteristic 2
+    // the derivative usually drops further, each missing degree adds a factor lc(f) to the resultant
+    let lc = f.last().unwrap().clone();
+    let missing = n - 1 - degree(&derivative);
+    let result = resultant(&f, &derivative);
+
+    match missing {
+        0 => gf_operations::gfdiv(result, lc),
+        _ => gf_operations::gfmul(&"gcm".to_string(), result, element_pow(&lc, missing as u128 - 1))
+    }
+}
+
+// Power of a single field element
+fn element_pow(e: &[u8], mut k: u128) -> Vec<u8> {
+    let semantic = "gcm".to_string();
+    let mut result = one()[0].clone();
+    let mut base = e.to_vec();
+
+    while k > 0 {
+        if k % 2 == 1 {
+            result = gf_operations::gfmul(&semantic, result, base.clone());
+        }
+        base = gf_operations::gfmul(&semantic, base.clone(), base);
+        k /= 2;
+    }
+    result
+}
+
 // Modular exponentiation for polynomials with BigUint exponent
 pub fn powmod_bigint(base: &Vec<Vec<u8>>, exponent: &BigUint, modulus: &Vec<Vec<u8>>) -> Vec<Vec<u8>> {
 
@@ -323,18 +720,34 @@ pub fn powmod_bigint(base: &Vec<Vec<u8>>, exponent: &BigUint, modulus: &Vec<Vec<
     result
 }
 
-fn random_poly(max_degree: usize) -> Vec<Vec<u8>> {
-    let deg_h = rand::thread_rng().gen_range(1..=max_degree);
+// Source of randomness for the randomized algorithms, so runs can be replayed from a seed
+pub trait PolyRng {
+    fn random_degree(&mut self, max_degree: usize) -> usize;
+    fn random_block(&mut self) -> Vec<u8>;
+}
+
+impl<R: RngCore> PolyRng for R {
+    fn random_degree(&mut self, max_degree: usize) -> usize {
+        self.gen_range(1..=max_degree)
+    }
+
+    fn random_block(&mut self) -> Vec<u8> {
+        let mut block = vec![0u8; 16];
+        self.fill_bytes(&mut block);
+        block
+    }
+}
+
+fn random_poly(rng: &mut dyn PolyRng, max_degree: usize) -> Vec<Vec<u8>> {
+    let deg_h = rng.random_degree(max_degree);
     let mut h = vec![vec![0u8; 16]; deg_h + 1];
     for coeff in &mut h {
-        for byte in coeff.iter_mut() {
-            *byte = rand::random();
-        }
+        *coeff = rng.random_block();
     }
     h
 }
 
-pub fn edf(f: &Vec<Vec<u8>>, d: usize) -> Vec<Vec<Vec<u8>>> {
+pub fn edf(f: &Vec<Vec<u8>>, d: usize, rng: &mut dyn PolyRng) -> Vec<Vec<Vec<u8>>> {
     let q: BigUint = BigUint::from(2u32).pow(128); // Compute q = 2^128
 
     let n = (f.len() - 1) / d; // Ensure integer division
@@ -350,7 +763,7 @@ pub fn edf(f: &Vec<Vec<u8>>, d: usize) -> Vec<Vec<Vec<u8>>> {
     while z.len() < n {
         // Generate a random polynomial 'h' of degree less than deg(f)
         let deg_f = f.len() -1;
-        let h = random_poly(deg_f - 1);
+        let h = random_poly(rng, deg_f - 1);
 
         // Compute g = (h^((q^d - 1)/3) - 1) mod f
         let mut g = powmod_bigint(&h, &exponent, f);
@@ -376,4 +789,633 @@ pub fn edf(f: &Vec<Vec<u8>>, d: usize) -> Vec<Vec<Vec<u8>>> {
         z = new_z; // Update 'z' with the new set of factors
     }
     sort(z) // Sort and return the list of factors of degree 'd'
-}
\ No newline at end of file
+}
+
+pub fn factor(f: &[Vec<u8>], algorithm: &str, rng: &mut dyn PolyRng) -> Result<Factorization, String> {
+    if algorithm != "berlekamp" && algorithm != "cantor_zassenhaus" {
+        return Err(format!("Unknown algorithm '{}', expected 'berlekamp' or 'cantor_zassenhaus'", algorithm));
+    }
+
+    // An empty polynomial is the zero polynomial
+    let f = pop_last_zeros(f.to_vec());
+    let leading_coefficient = f.last().cloned().unwrap_or(vec![0u8; 16]);
+    if degree(&f) < 1 {
+        return Ok((leading_coefficient, Vec::new()));
+    }
+
+    let mut factors: Vec<(Vec<Vec<u8>>, u128)> = Vec::new();
+
+    for (squarefree, e) in sff(&make_monic(&f)) {
+        match algorithm {
+            // Split every squarefree part directly with the Berlekamp subalgebra
+            "berlekamp" => {
+                for irreducible in berlekamp(&squarefree) {
+                    factors.push((irreducible, e));
+                }
+            }
+            // Squarefree parts -> parts with factors of equal degree -> irreducible factors
+            _ => {
+                for (part, d) in ddf(&squarefree) {
+                    for irreducible in edf(&part, d as usize, rng) {
+                        factors.push((irreducible, e));
+                    }
+                }
+            }
+        }
+    }
+    Ok((leading_coefficient, sort_tuples(factors)))
+}
+
+pub fn berlekamp(f: &[Vec<u8>]) -> Vec<Vec<Vec<u8>>> {
+    let f = make_monic(&pop_last_zeros(f.to_vec()));
+    let n = degree(&f);
+    if n <= 1 {
+        return vec![f];
+    }
+    let n = n as usize;
+
+    // Represent the polynomial 'x'
+    let mut x = vec![vec![0u8; 16]; 2];
+    x[1][0] = 0x80;
+
+    // Row i of Q holds x^{q*i} mod f, we store (Q - I) transposed so that its
+    // kernel consists of the polynomials g with g^q = g mod f
+    let x_q = frobenius_power(&x, &f, 1);
+    let mut rows = vec![one()];
+    for i in 1..n {
+        let (_, row) = divmod(&mul(&rows[i - 1], &x_q), &f);
+        rows.push(row);
+    }
+
+    let mut matrix = vec![vec![vec![0u8; 16]; n]; n];
+    for (i, row) in rows.iter().enumerate() {
+        for (j, coefficient) in row.iter().enumerate() {
+            matrix[j][i] = coefficient.clone();
+        }
+        matrix[i][i][0] ^= 0x80; // Subtract the identity
+    }
+
+    // The dimension of the kernel is the number of irreducible factors
+    let basis = gf_matrix::nullspace(&matrix).expect("Berlekamp matrix is square and non-empty");
+    let r = basis.len();
+    let mut factors = vec![f.clone()];
+
+    for g in basis {
+        let g = pop_last_zeros(g);
+        if degree(&g) < 1 {
+            continue; // Constants do not separate any factors
+        }
+
+        // Precompute g^{2^i} mod f for the trace map
+        let mut squares = vec![g];
+        for i in 1..128 {
+            let (_, square) = divmod(&mul(&squares[i - 1], &squares[i - 1]), &f);
+            squares.push(square);
+        }
+
+        // Tr(c * g) is 0 or 1 modulo every irreducible factor, so its gcd with a factor splits
+        // it deterministically; walking c over the basis alpha^j separates all factor values
+        for j in 0..128 {
+            if factors.len() == r {
+                return sort(factors);
+            }
+
+            let mut c = vec![0u8; 16];
+            c[j / 8] = 0x80 >> (j % 8);
+
+            let mut trace = zero();
+            for square in &squares {
+                trace = add(&trace, &mul(square, &vec![c.clone()]));
+                c = gf_operations::gfmul(&"gcm".to_string(), c.clone(), c);
+            }
+
+            let mut new_factors = Vec::new();
+            for u in factors {
+                let h = gcd(&u, &trace);
+                if degree(&h) > 0 && degree(&h) < degree(&u) {
+                    let (quotient, _) = divmod(&u, &h);
+                    new_factors.push(h);
+                    new_factors.push(quotient);
+                } else {
+                    new_factors.push(u);
+                }
+            }
+            factors = new_factors;
+        }
+    }
+    sort(factors)
+}
+
+pub fn is_irreducible(f: &[Vec<u8>]) -> bool {
+    let f = make_monic(&pop_last_zeros(f.to_vec()));
+    let n = degree(&f);
+    if n < 1 {
+        return false;
+    }
+
+    // Represent the polynomial 'x'
+    let mut x = vec![vec![0u8; 16]; 2];
+    x[1][0] = 0x80;
+
+    // Rabin: f has no irreducible factor of degree n/p for any prime p dividing n ...
+    for p in prime_divisors(n as usize) {
+        let h = frobenius_power(&x, &f, n as usize / p);
+        if gcd(&add(&h, &x), &f) != one() {
+            return false;
+        }
+    }
+
+    // ... and f divides x^{q^n} - x
+    let h = frobenius_power(&x, &f, n as usize);
+    let (_, r) = divmod(&add(&h, &x), &f);
+    is_zero(&r)
+}
+
+pub fn random_irreducible(degree: usize, seed: u64) -> Result<Vec<Vec<u8>>, String> {
+    // Constants are never irreducible, the search would not terminate
+    if degree == 0 {
+        return Err("Degree of an irreducible polynomial must be at least 1".to_string());
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    // Roughly every n-th monic polynomial of degree n is irreducible, so just keep drawing
+    loop {
+        let mut f = vec![vec![0u8; 16]; degree + 1];
+        for coeff in &mut f[..degree] {
+            rng.fill(&mut coeff[..]);
+        }
+        f[degree][0] = 0x80;
+
+        if is_irreducible(&f) {
+            return Ok(f);
+        }
+    }
+}
+
+// Computes h^{q^k} mod f with q = 2^128
+fn frobenius_power(h: &[Vec<u8>], f: &[Vec<u8>], k: usize) -> Vec<Vec<u8>> {
+    let f = f.to_vec();
+    let mut h = h.to_vec();
+    for _ in 0..k {
+        // q = 2^128 does not fit into the exponent, so raise to 2^64 twice
+        h = powmod(&h, &f, 1 << 64);
+        h = powmod(&h, &f, 1 << 64);
+    }
+    h
+}
+
+fn prime_divisors(mut n: usize) -> Vec<usize> {
+    let mut primes = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            primes.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+        p += 1;
+    }
+    if n > 1 {
+        primes.push(n);
+    }
+    primes
+}
+
+pub fn roots(f: &[Vec<u8>], rng: &mut dyn PolyRng) -> Vec<Vec<u8>> {
+    let f = pop_last_zeros(f.to_vec());
+    if degree(&f) < 1 {
+        return Vec::new();
+    }
+
+    // Represent the polynomial 'x'
+    let mut x = vec![vec![0u8; 16]; 2];
+    x[1][0] = 0x80;
+
+    // Compute h = x^{2^128} mod f, every element of GF(2^128) is a root of x^{2^128} - x
+    let mut h = x.clone();
+    for _ in 0..128 {
+        (_, h) = divmod(&mul(&h, &h), &f);
+    }
+
+    // The gcd with x^{2^128} - x is the product of all distinct linear factors of f
+    let g = gcd(&add(&h, &x), &f);
+    if degree(&g) < 1 {
+        return Vec::new();
+    }
+
+    // Split the linear part and read the root off the constant coefficient (x + r has root r)
+    edf(&g, 1, rng).into_iter().map(|factor| factor[0].clone()).collect()
+}
+
+pub fn roots_multiplicity(f: &[Vec<u8>], rng: &mut dyn PolyRng) -> Vec<(Vec<u8>, u128)> {
+    let f = pop_last_zeros(f.to_vec());
+    let mut result: Vec<(Vec<u8>, u128)> = Vec::new();
+
+    for root in roots(&f, rng) {
+        let linear = vec![root.clone(), one()[0].clone()];
+
+        // Count how often (x + r) divides f
+        let mut multiplicity: u128 = 0;
+        let mut rest = f.clone();
+        loop {
+            let (q, r) = divmod(&rest, &linear);
+            if !is_zero(&r) {
+                break;
+            }
+            multiplicity += 1;
+            rest = q;
+        }
+        result.push((root, multiplicity));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded_poly(rng: &mut StdRng, degree: usize) -> Vec<Vec<u8>> {
+        let mut poly = vec![vec![0u8; 16]; degree + 1];
+        for coeff in &mut poly {
+            rng.fill(&mut coeff[..]);
+        }
+        poly
+    }
+
+    // Plain extended Euclid as reference for the half-GCD based xgcd
+    fn xgcd_classic(a: &[Vec<u8>], b: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        let (mut r0, mut r1) = (a.to_vec(), b.to_vec());
+        while !is_zero(&r1) {
+            let (_, r) = divmod(&r0, &r1);
+            r0 = r1;
+            r1 = r;
+        }
+        make_monic(&r0)
+    }
+
+    #[test]
+    fn xgcd_bezout_identity_small() {
+        let mut rng = StdRng::seed_from_u64(26);
+        let common = seeded_poly(&mut rng, 3);
+        let a = mul(&common, &seeded_poly(&mut rng, 4));
+        let b = mul(&common, &seeded_poly(&mut rng, 2));
+
+        let (g, u, v) = xgcd(&a, &b);
+        assert_eq!(g, gcd(&a, &b));
+        assert_eq!(add(&mul(&u, &a), &mul(&v, &b)), g);
+    }
+
+    #[test]
+    fn xgcd_bezout_identity_half_gcd() {
+        let mut rng = StdRng::seed_from_u64(126);
+        let common = seeded_poly(&mut rng, 7);
+        let a = mul(&common, &seeded_poly(&mut rng, 41));
+        let b = mul(&common, &seeded_poly(&mut rng, 33));
+
+        let (g, u, v) = xgcd(&a, &b);
+        assert_eq!(g, xgcd_classic(&a, &b));
+        assert_eq!(add(&mul(&u, &a), &mul(&v, &b)), g);
+    }
+
+    #[test]
+    fn xgcd_with_zero() {
+        let a = vec![vec![0u8; 16], vec![0x40; 16]];
+        let (g, u, v) = xgcd(&zero(), &a);
+        assert_eq!(g, make_monic(&a));
+        assert_eq!(add(&mul(&u, &zero()), &mul(&v, &a)), g);
+    }
+
+    #[test]
+    fn roots_with_multiplicities() {
+        let mut rng = StdRng::seed_from_u64(27);
+        let r1 = seeded_poly(&mut rng, 0).remove(0);
+        let r2 = seeded_poly(&mut rng, 0).remove(0);
+        let l1 = vec![r1.clone(), one()[0].clone()];
+        let l2 = vec![r2.clone(), one()[0].clone()];
+
+        // f = (x + r1)^3 * (x + r2)
+        let f = mul(&pow(&l1, 3), &l2);
+
+        let mut expected = vec![(r1, 3), (r2, 1)];
+        expected.sort_by(|a, b| comparator(&vec![a.0.clone()], &vec![b.0.clone()]));
+
+        assert_eq!(roots_multiplicity(&f, &mut rng), expected);
+    }
+
+    #[test]
+    fn roots_of_constant() {
+        let mut rng = StdRng::seed_from_u64(127);
+        assert!(roots(&one(), &mut rng).is_empty());
+        assert!(roots(&zero(), &mut rng).is_empty());
+    }
+
+    #[test]
+    fn factor_reconstructs_input() {
+        let mut rng = StdRng::seed_from_u64(28);
+        let l1 = vec![seeded_poly(&mut rng, 0).remove(0), one()[0].clone()];
+        let q = make_monic(&seeded_poly(&mut rng, 3));
+        let lc = seeded_poly(&mut rng, 0);
+
+        // f = lc * (x + r)^2 * q^3
+        let f = mul(&mul(&lc, &pow(&l1, 2)), &pow(&q, 3));
+
+        let (leading_coefficient, factors) = factor(&f, "cantor_zassenhaus", &mut rng).unwrap();
+        assert_eq!(vec![leading_coefficient], lc);
+
+        let mut product = lc.clone();
+        for (factor, e) in &factors {
+            assert_eq!(factor.last().unwrap(), &one()[0]);
+            product = mul(&product, &pow(factor, *e));
+        }
+        assert_eq!(product, f);
+        assert_eq!(factors, sort_tuples(factors.clone()));
+        assert!(factors.contains(&(l1, 2)));
+    }
+
+    #[test]
+    fn factor_zero() {
+        let mut rng = StdRng::seed_from_u64(128);
+        assert_eq!(factor(&zero(), "cantor_zassenhaus", &mut rng), Ok((vec![0u8; 16], Vec::new())));
+        assert_eq!(factor(&[], "berlekamp", &mut rng), Ok((vec![0u8; 16], Vec::new())));
+    }
+
+    #[test]
+    fn is_irreducible_rejects_products() {
+        let f = random_irreducible(2, 29).unwrap();
+        let g = random_irreducible(3, 29).unwrap();
+
+        assert!(is_irreducible(&f));
+        assert!(is_irreducible(&g));
+        assert!(!is_irreducible(&mul(&f, &g)));
+        assert!(!is_irreducible(&pow(&f, 2)));
+        assert!(!is_irreducible(&one()));
+    }
+
+    #[test]
+    fn random_irreducible_is_reproducible() {
+        assert_eq!(random_irreducible(3, 1029).unwrap(), random_irreducible(3, 1029).unwrap());
+        assert!(random_irreducible(0, 1029).is_err());
+    }
+
+    #[test]
+    fn ddf_of_random_irreducible() {
+        for degree in 1..=4 {
+            for seed in 0..3 {
+                let f = random_irreducible(degree, seed).unwrap();
+                assert_eq!(f.len(), degree + 1);
+                assert_eq!(ddf(&f), vec![(f.clone(), degree as u128)]);
+            }
+        }
+    }
+
+    #[test]
+    fn ddf_of_product_of_random_irreducibles() {
+        let f = random_irreducible(2, 7).unwrap();
+        let g = random_irreducible(2, 8).unwrap();
+        let h = random_irreducible(3, 9).unwrap();
+
+        let result = ddf(&mul(&mul(&f, &g), &h));
+        assert_eq!(result, sort_tuples(vec![(mul(&f, &g), 2), (h, 3)]));
+    }
+
+    #[test]
+    fn berlekamp_matches_edf() {
+        let f = random_irreducible(2, 30).unwrap();
+        let g = random_irreducible(2, 31).unwrap();
+        let h = random_irreducible(2, 32).unwrap();
+        let product = mul(&mul(&f, &g), &h);
+
+        assert_eq!(berlekamp(&product), sort(vec![f, g, h]));
+        assert_eq!(berlekamp(&product), edf(&product, 2, &mut StdRng::seed_from_u64(30)));
+    }
+
+    #[test]
+    fn berlekamp_mixed_degrees() {
+        let f = random_irreducible(1, 33).unwrap();
+        let g = random_irreducible(3, 34).unwrap();
+        let h = random_irreducible(2, 35).unwrap();
+
+        assert_eq!(berlekamp(&g), vec![g.clone()]);
+        assert_eq!(berlekamp(&mul(&mul(&f, &g), &h)), sort(vec![f, g, h]));
+    }
+
+    #[test]
+    fn factor_algorithms_agree() {
+        let f = random_irreducible(2, 36).unwrap();
+        let g = random_irreducible(1, 37).unwrap();
+        let input = mul(&pow(&f, 2), &pow(&g, 3));
+
+        let mut rng = StdRng::seed_from_u64(38);
+        assert_eq!(factor(&input, "berlekamp", &mut rng), factor(&input, "cantor_zassenhaus", &mut rng));
+        assert!(factor(&input, "berlekampp", &mut rng).is_err());
+    }
+
+    #[test]
+    fn edf_with_seeded_and_custom_rng() {
+        let f = random_irreducible(1, 40).unwrap();
+        let g = random_irreducible(1, 41).unwrap();
+        let h = random_irreducible(1, 42).unwrap();
+        let product = mul(&mul(&f, &g), &h);
+
+        // Any deterministic PolyRng can drive edf, here one that counts up the coefficients
+        struct Counter(u8);
+        impl PolyRng for Counter {
+            fn random_degree(&mut self, max_degree: usize) -> usize {
+                max_degree
+            }
+            fn random_block(&mut self) -> Vec<u8> {
+                self.0 = self.0.wrapping_add(1);
+                vec![self.0; 16]
+            }
+        }
+
+        // Passes the draws of a seeded StdRng through and keeps the random coefficients
+        struct Recorder(StdRng, Vec<Vec<u8>>);
+        impl PolyRng for Recorder {
+            fn random_degree(&mut self, max_degree: usize) -> usize {
+                self.0.random_degree(max_degree)
+            }
+            fn random_block(&mut self) -> Vec<u8> {
+                let block = self.0.random_block();
+                self.1.push(block.clone());
+                block
+            }
+        }
+        let run = |seed: u64| {
+            let mut recorder = Recorder(StdRng::seed_from_u64(seed), Vec::new());
+            assert_eq!(edf(&product, 1, &mut recorder), sort(vec![f.clone(), g.clone(), h.clone()]));
+            recorder.1
+        };
+
+        // The same seed draws the same polynomials, another seed different ones
+        let draws = run(31);
+        assert!(!draws.is_empty());
+        assert_eq!(run(31), draws);
+        assert_ne!(run(32), draws);
+
+        // The custom rng is really used: two polynomials of degree 2 split the product
+        let mut counter = Counter(0);
+        assert_eq!(edf(&product, 1, &mut counter), sort(vec![f, g, h]));
+        assert_eq!(counter.0, 6);
+    }
+
+    #[test]
+    fn compose_mod_matches_compose() {
+        let mut rng = StdRng::seed_from_u64(32);
+        let f = seeded_poly(&mut rng, 11);
+        let g = seeded_poly(&mut rng, 6);
+        let m = seeded_poly(&mut rng, 5);
+
+        let (_, expected) = divmod(&compose(&f, &g), &m);
+        assert_eq!(compose_mod(&f, &g, &m), Ok(expected));
+    }
+
+    #[test]
+    fn compose_mod_zero_modulus() {
+        let mut rng = StdRng::seed_from_u64(133);
+        let f = seeded_poly(&mut rng, 4);
+        let g = seeded_poly(&mut rng, 2);
+
+        assert!(compose_mod(&f, &g, &zero()).is_err());
+        assert!(compose_mod(&f, &g, &[]).is_err());
+    }
+
+    #[test]
+    fn compose_normal() {
+        let mut rng = StdRng::seed_from_u64(33);
+        let a = seeded_poly(&mut rng, 0);
+
+        // f = x^2 + 1 and g = x + a give f(g) = x^2 + a^2 + 1
+        let f = vec![one()[0].clone(), vec![0u8; 16], one()[0].clone()];
+        let g = vec![a[0].clone(), one()[0].clone()];
+        let expected = add(&add(&pow(&a, 2), &one()), &vec![vec![0u8; 16], vec![0u8; 16], one()[0].clone()]);
+
+        assert_eq!(compose(&f, &g), expected);
+        assert_eq!(compose(&f, &zero()), one());
+    }
+
+    #[test]
+    fn ddf_bsgs_matches_ddf() {
+        let parts = [(1, 50), (1, 51), (2, 52), (3, 53), (3, 54), (4, 55), (6, 56)];
+        let mut f = one();
+        for (degree, seed) in parts {
+            f = mul(&f, &random_irreducible(degree, seed).unwrap());
+            assert_eq!(ddf_bsgs(&f), ddf(&f));
+        }
+        assert_eq!(ddf_bsgs(&one()), ddf(&one()));
+    }
+
+    #[test]
+    fn resultant_detects_common_factor() {
+        let mut rng = StdRng::seed_from_u64(34);
+        let common = seeded_poly(&mut rng, 2);
+        let a = seeded_poly(&mut rng, 5);
+        let b = seeded_poly(&mut rng, 4);
+
+        assert_eq!(gcd(&a, &b), one());
+        assert_ne!(resultant(&a, &b), vec![0u8; 16]);
+        assert_eq!(resultant(&mul(&a, &common), &mul(&b, &common)), vec![0u8; 16]);
+    }
+
+    #[test]
+    fn resultant_with_linear_is_evaluation() {
+        let mut rng = StdRng::seed_from_u64(134);
+        let r = seeded_poly(&mut rng, 0);
+        let b = seeded_poly(&mut rng, 6);
+
+        // res(x + r, B) = B(r)
+        let linear = vec![r[0].clone(), one()[0].clone()];
+        assert_eq!(resultant(&linear, &b), compose(&b, &r)[0]);
+        assert_eq!(resultant(&b, &linear), compose(&b, &r)[0]);
+    }
+
+    #[test]
+    fn resultant_is_multiplicative() {
+        let mut rng = StdRng::seed_from_u64(234);
+        let a = seeded_poly(&mut rng, 4);
+        let b1 = seeded_poly(&mut rng, 3);
+        let b2 = seeded_poly(&mut rng, 5);
+
+        let expected = gf_operations::gfmul(&"gcm".to_string(), resultant(&a, &b1), resultant(&a, &b2));
+        assert_eq!(resultant(&a, &mul(&b1, &b2)), expected);
+    }
+
+    #[test]
+    fn discriminant_detects_squarefree() {
+        let mut rng = StdRng::seed_from_u64(334);
+        for degree in 1..8 {
+            let f = seeded_poly(&mut rng, degree);
+            let squarefree = gcd(&f, &diff(f.clone())) == one();
+            assert_eq!(discriminant(&f) != vec![0u8; 16], squarefree);
+
+            let g = mul(&f, &seeded_poly(&mut rng, 1));
+            assert_eq!(discriminant(&mul(&g, &g)), vec![0u8; 16]);
+        }
+    }
+
+    #[test]
+    fn discriminant_of_quadratic() {
+        let mut rng = StdRng::seed_from_u64(434);
+        let f = seeded_poly(&mut rng, 2);
+
+        // For a*x^2 + b*x + c the discriminant in characteristic 2 is b^2
+        assert_eq!(discriminant(&f), element_pow(&f[1], 2));
+    }
+
+    #[test]
+    fn crt_normal() {
+        let mut rng = StdRng::seed_from_u64(35);
+        let moduli = vec![random_irreducible(2, 35).unwrap(), random_irreducible(3, 36).unwrap(), random_irreducible(1, 37).unwrap()];
+        let residues: Vec<Vec<Vec<u8>>> = moduli
+            .iter()
+            .map(|m| divmod(&seeded_poly(&mut rng, 4), m).1)
+            .collect();
+
+        let (result, modulus) = crt(&residues, &moduli).unwrap();
+        assert_eq!(modulus, mul(&mul(&moduli[0], &moduli[1]), &moduli[2]));
+        assert!(degree(&result) < degree(&modulus));
+        for (r, m) in residues.iter().zip(&moduli) {
+            assert_eq!(&divmod(&result, m).1, r);
+        }
+    }
+
+    #[test]
+    fn crt_not_coprime() {
+        let f = random_irreducible(2, 38).unwrap();
+        let moduli = vec![mul(&f, &random_irreducible(1, 39).unwrap()), random_irreducible(3, 40).unwrap(), f];
+        let residues = vec![one(), one(), one()];
+
+        assert_eq!(crt(&residues, &moduli), Err("Moduli 0 and 2 are not coprime".to_string()));
+    }
+
+    #[test]
+    fn invmod_normal() {
+        let mut rng = StdRng::seed_from_u64(226);
+        let m = seeded_poly(&mut rng, 40);
+        let a = seeded_poly(&mut rng, 25);
+
+        let inverse = invmod(&a, &m).unwrap().unwrap();
+        let (_, product) = divmod(&mul(&a, &inverse), &m);
+        assert_eq!(product, one());
+    }
+
+    #[test]
+    fn invmod_not_coprime() {
+        let mut rng = StdRng::seed_from_u64(326);
+        let common = seeded_poly(&mut rng, 2);
+        let m = mul(&common, &seeded_poly(&mut rng, 5));
+        let a = mul(&common, &seeded_poly(&mut rng, 3));
+
+        assert_eq!(invmod(&a, &m), Ok(None));
+    }
+
+    #[test]
+    fn invmod_zero_modulus() {
+        let a = seeded_poly(&mut StdRng::seed_from_u64(426), 3);
+
+        assert!(invmod(&a, &zero()).is_err());
+        assert!(invmod(&a, &[vec![0u8; 16], vec![0u8; 16]]).is_err());
+        assert!(invmod(&a, &[]).is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use super::{aes_sea_128, gcm};
+
+// Tag, H, the encrypted counter block E(K, J0) (Y0 encrypted in gcm) and L
+type GmacOutput = (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>);
+
+// GMAC is GCM without plaintext, the data is authenticated as associated data
+pub fn compute(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, data: Vec<u8>, tag_length: usize) -> Result<GmacOutput, String> {
+    aes_sea_128::check_key(&algorithm, &key)?;
+    gcm::check_tag_length(tag_length)?;
+    gcm::check_nonce(&nonce)?;
+    let mode = &"encrypt".to_string();
+
+    let auth_key = aes_sea_128::execute(&algorithm, mode, &key, vec![0; 16])?;
+    let y0 = gcm::initial_counter(&nonce, &auth_key);
+    let y0_encrypted = aes_sea_128::execute(&algorithm, mode, &key, y0)?;
+
+    let (mut q, l) = gcm::ghash(Vec::new(), auth_key.clone(), data); // Run the GHASH function, gives back Q and L
+
+    // XOR bytewise of Q and Y0
+    for (q, y) in q.iter_mut().zip(&y0_encrypted) {
+        *q ^= y;
+    }
+    q.truncate(tag_length);
+
+    Ok((q, auth_key, y0_encrypted, l))
+}
+
+pub fn verify(algorithm: String, nonce: Vec<u8>, key: Vec<u8>, data: Vec<u8>, tag: Vec<u8>, tag_length: usize) -> Result<(bool, GmacOutput), String> {
+    if tag.len() != tag_length {
+        return Err(format!("Tag has {} bytes, expected {}", tag.len(), tag_length));
+    }
+
+    let output = compute(algorithm, nonce, key, data, tag_length)?;
+    let authentic = openssl::memcmp::eq(&output.0, &tag); // Compare in constant time
+    Ok((authentic, output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::de_encode_base64::decode_str;
+
+    #[test]
+    fn gmac_aes128() {
+        let nonce = decode_str("yv66vvrO263eyviI");
+        let key = decode_str("/v/pkoZlcxxtao+UZzCDCA==");
+        let data = decode_str("/u36zt6tvu/+7frO3q2+76ut2tI=");
+
+        let tag = decode_str("NGQ0/VHVzQxYh+xj45uQeg==");
+        let h = decode_str("uDtTNwi/U10KpuUpgNU7eA==");
+        let y0_encrypted = decode_str("MkcYSzxPaaRNvNIoh7u0GA==");
+
+        let (result_tag, result_h, result_y0, _) = compute("aes128".to_string(), nonce, key, data, 16).unwrap();
+        assert_eq!((result_tag, result_h, result_y0), (tag, h, y0_encrypted));
+    }
+
+    #[test]
+    fn gmac_equals_gcm_without_plaintext() {
+        let nonce = decode_str("4gF+BtR3ku/PUQci");
+        let key = decode_str("Xjq/GkpTSWoe3ZH0F+tjrQ==");
+        let data = decode_str("QUQtRGF0ZW4=");
+
+        let (_, tag, l, h) = gcm::encrypt("sea128".to_string(), nonce.clone(), key.clone(), Vec::new(), data.clone(), 16).unwrap();
+        let result = compute("sea128".to_string(), nonce, key, data, 16).unwrap();
+        assert_eq!((result.0, result.1, result.3), (tag, h, l));
+    }
+
+    #[test]
+    fn verify_truncated_tag() {
+        let nonce = decode_str("yv66vvrO263eyviI");
+        let key = decode_str("/v/pkoZlcxxtao+UZzCDCA==");
+        let data = decode_str("/u36zt6tvu/+7frO3q2+76ut2tI=");
+        let tag = decode_str("NGQ0/VHVzQxYh+xj");
+
+        let (authentic, _) = verify("aes128".to_string(), nonce.clone(), key.clone(), data.clone(), tag.clone(), 12).unwrap();
+        assert!(authentic);
+
+        let (authentic, _) = verify("aes128".to_string(), nonce.clone(), key.clone(), data[1..].to_vec(), tag.clone(), 12).unwrap();
+        assert!(!authentic);
+        assert!(verify("aes128".to_string(), nonce, key, data, tag, 16).is_err());
+    }
+
+    #[test]
+    fn empty_nonce() {
+        let key = decode_str("/v/pkoZlcxxtao+UZzCDCA==");
+
+        assert!(compute("aes128".to_string(), Vec::new(), key.clone(), Vec::new(), 16).is_err());
+        assert!(verify("aes128".to_string(), Vec::new(), key, Vec::new(), vec![0; 16], 16).is_err());
+    }
+}
@@ -9,4 +9,17 @@ pub mod xex;
 pub mod gcm;
 pub mod padding_oracle;
 pub mod gfpoly_operations;
-pub mod gcm_crack;
\ No newline at end of file
+pub mod gcm_crack;
+pub mod berlekamp_massey;
+pub mod poly_notation;
+pub mod gf_matrix;
+pub mod bitmatrix;
+pub mod gf2poly;
+pub mod gcm_stream;
+pub mod gmac;
+pub mod gcm_siv;
+pub mod chacha20poly1305;
+pub mod ccm;
+pub mod eax;
+pub mod block_modes;
+pub mod aead;
\ No newline at end of file
@@ -0,0 +1,261 @@
+use serde_json::{json, Value};
+use super::{block2poly, de_encode_base64, poly2byte};
+
+// Textual notation for polynomials over GF(2^128) such as "x^3 + (α^7+α+1)·x + 1", coefficients are
+// written in α with the gcm semantic of block2poly/poly2block or as raw blocks in hex like "0x80000000..."
+
+pub fn poly_to_text(poly: &[Vec<u8>], hex: bool) -> String {
+    let mut terms: Vec<String> = Vec::new();
+
+    for (degree, coefficient) in poly.iter().enumerate().rev() {
+        if coefficient.iter().all(|&x| x == 0) {
+            continue;
+        }
+
+        let monomial = match degree {
+            0 => String::new(),
+            1 => "x".to_string(),
+            _ => format!("x^{}", degree)
+        };
+        let coefficient = element_to_text(coefficient, hex);
+
+        let term = if monomial.is_empty() {
+            // Parenthesize a constant sum as well as soon as there are other terms
+            if coefficient.contains('+') && !terms.is_empty() {
+                format!("({})", coefficient)
+            } else {
+                coefficient
+            }
+        } else if coefficient == "1" {
+            monomial
+        } else if coefficient.contains('+') {
+            format!("({})·{}", coefficient, monomial)
+        } else {
+            format!("{}·{}", coefficient, monomial)
+        };
+        terms.push(term);
+    }
+
+    if terms.is_empty() {
+        return "0".to_string();
+    }
+    terms.join(" + ")
+}
+
+pub fn element_to_text(block: &[u8], hex: bool) -> String {
+    if hex {
+        return format!("0x{}", block.iter().map(|byte| format!("{:02x}", byte)).collect::<String>());
+    }
+
+    let coefficients = block2poly::execute(&"gcm".to_string(), de_encode_base64::encode(block));
+    if coefficients.is_empty() {
+        return "0".to_string();
+    }
+
+    let terms: Vec<String> = coefficients
+        .iter()
+        .rev()
+        .map(|&exponent| match exponent {
+            0 => "1".to_string(),
+            1 => "α".to_string(),
+            _ => format!("α^{}", exponent)
+        })
+        .collect();
+    terms.join("+")
+}
+
+pub fn text_to_poly(text: &str) -> Result<Vec<Vec<u8>>, String> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut poly: Vec<Vec<u8>> = vec![vec![0u8; 16]];
+
+    for term in split_top_level(&text)? {
+        let (coefficient, degree) = parse_term(&term)?;
+        if poly.len() <= degree {
+            poly.resize(degree + 1, vec![0u8; 16]);
+        }
+        for (byte, c) in poly[degree].iter_mut().zip(&coefficient) {
+            *byte ^= c;
+        }
+    }
+
+    // Remove leading zero coefficients, but keep the zero polynomial as a single block
+    while poly.len() > 1 && poly.last().unwrap().iter().all(|&x| x == 0) {
+        poly.pop();
+    }
+    Ok(poly)
+}
+
+pub fn text_to_element(text: &str) -> Result<Vec<u8>, String> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let text = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')).unwrap_or(&text);
+
+    let mut element = vec![0u8; 16];
+    for atom in text.split('+') {
+        for (byte, c) in element.iter_mut().zip(&parse_atom(atom)?) {
+            *byte ^= c;
+        }
+    }
+    Ok(element)
+}
+
+pub fn encode_poly(poly: Vec<Vec<u8>>, format: &Option<String>) -> Value {
+    match format.as_deref() {
+        Some("text") => json!(poly_to_text(&poly, false)),
+        Some("hex") => json!(poly_to_text(&poly, true)),
+        _ => json!(de_encode_base64::encode_vectors(poly))
+    }
+}
+
+pub fn encode_element(block: Vec<u8>, format: &Option<String>) -> Value {
+    match format.as_deref() {
+        Some("text") => json!(element_to_text(&block, false)),
+        Some("hex") => json!(element_to_text(&block, true)),
+        _ => json!(de_encode_base64::encode(block))
+    }
+}
+
+// Splits at every '+' that is not inside a parenthesized coefficient
+fn split_top_level(text: &str) -> Result<Vec<String>, String> {
+    let mut terms = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+
+    for c in text.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Err(format!("Unbalanced ')' in '{}'", text)),
+            ')' => depth -= 1,
+            '+' if depth == 0 => {
+                terms.push(current.clone());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if depth != 0 {
+        return Err(format!("Unbalanced '(' in '{}'", text));
+    }
+    terms.push(current);
+
+    if terms.iter().any(|term| term.is_empty()) {
+        return Err(format!("Empty term in '{}'", text));
+    }
+    Ok(terms)
+}
+
+// A term is [coefficient][·|*][x[^degree]] with at least one of both parts
+fn parse_term(term: &str) -> Result<(Vec<u8>, usize), String> {
+    // The coefficient is a parenthesized sum, a hex block or everything in front of the 'x'
+    let (coefficient, monomial) = if term.starts_with('(') {
+        let end = term.find(')').ok_or(format!("Unbalanced '(' in '{}'", term))?;
+        term.split_at(end + 1)
+    } else if let Some(hex) = term.strip_prefix("0x") {
+        term.split_at(2 + hex.chars().take_while(|c| c.is_ascii_hexdigit()).count())
+    } else {
+        term.split_at(term.find('x').unwrap_or(term.len()))
+    };
+    let coefficient = coefficient.trim_end_matches(['·', '*']);
+    let monomial = monomial.trim_start_matches(['·', '*']);
+
+    let coefficient = match (coefficient, monomial) {
+        ("", "") => return Err(format!("Invalid term '{}'", term)),
+        ("", _) => text_to_element("1")?,
+        _ => text_to_element(coefficient)?
+    };
+
+    let degree = match monomial {
+        "" => 0,
+        "x" => 1,
+        _ => monomial
+            .strip_prefix("x^")
+            .and_then(|exponent| exponent.parse().ok())
+            .ok_or(format!("Invalid monomial '{}'", monomial))?
+    };
+    Ok((coefficient, degree))
+}
+
+fn parse_atom(atom: &str) -> Result<Vec<u8>, String> {
+    if let Some(hex) = atom.strip_prefix("0x") {
+        if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Hex coefficient '{}' needs exactly 32 hex digits", atom));
+        }
+        return Ok((0..16).map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap()).collect());
+    }
+
+    let exponent: u32 = match atom {
+        "0" => return Ok(vec![0u8; 16]),
+        "1" => 0,
+        "α" => 1,
+        _ => atom
+            .strip_prefix("α^")
+            .and_then(|exponent| exponent.parse().ok())
+            .ok_or(format!("Invalid coefficient '{}'", atom))?
+    };
+    if exponent > 127 {
+        return Err(format!("Exponent of '{}' is larger than 127", atom));
+    }
+    Ok(poly2byte::execute(&"gcm".to_string(), vec![exponent as u8]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_to_poly_normal() {
+        let result = text_to_poly("x^3 + (α^7+α+1)·x + 1").unwrap();
+
+        let expected = vec![
+            poly2byte::execute(&"gcm".to_string(), vec![0]),
+            poly2byte::execute(&"gcm".to_string(), vec![0, 1, 7]),
+            vec![0u8; 16],
+            poly2byte::execute(&"gcm".to_string(), vec![0]),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn poly_to_text_round_trip() {
+        let poly = de_encode_base64::decode_vectors(vec![
+            "q4AAAAAAAAAAAAAAAAAAAA==".to_string(),
+            "AAAAAAAAAAAAAAAAAAAAAA==".to_string(),
+            "QAAAAAAAAAAAAAAAAAAAAA==".to_string(),
+            "gAAAAAAAAAAAAAAAAAAAAA==".to_string(),
+        ]);
+
+        let text = poly_to_text(&poly, false);
+        assert_eq!(text, "x^3 + α·x^2 + (α^8+α^7+α^6+α^4+α^2+1)");
+        assert_eq!(text_to_poly(&text).unwrap(), poly);
+
+        let hex = poly_to_text(&poly, true);
+        assert_eq!(hex, "0x80000000000000000000000000000000·x^3 + 0x40000000000000000000000000000000·x^2 + 0xab800000000000000000000000000000");
+        assert_eq!(text_to_poly(&hex).unwrap(), poly);
+    }
+
+    #[test]
+    fn element_matches_block2poly() {
+        let block = "ARIAAAAAAAAAAAAAAAAAgA==".to_string();
+        let coefficients = block2poly::execute(&"gcm".to_string(), block.clone());
+
+        let text = element_to_text(&de_encode_base64::decode(block.clone()).unwrap(), false);
+        assert_eq!(text.split('+').count(), coefficients.len());
+        assert_eq!(de_encode_base64::encode(text_to_element(&text).unwrap()), block);
+    }
+
+    #[test]
+    fn zero_polynomial() {
+        assert_eq!(poly_to_text(&[vec![0u8; 16]], false), "0");
+        assert_eq!(text_to_poly("0").unwrap(), vec![vec![0u8; 16]]);
+        assert_eq!(text_to_poly("x + x").unwrap(), vec![vec![0u8; 16]]);
+    }
+
+    #[test]
+    fn invalid_notation() {
+        assert!(text_to_poly("x^3 + (α+1·x").is_err());
+        assert!(text_to_poly("x^ + 1").is_err());
+        assert!(text_to_poly("α^128·x").is_err());
+        assert!(text_to_poly("0x1234·x").is_err());
+    }
+}
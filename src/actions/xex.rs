@@ -1,30 +1,36 @@
 use crate::actions::{gf_operations, aes_sea_128};
 use super::de_encode_base64;
 
-pub fn execute(mode: String, key: String, tweak: String, input: String) -> Vec<u8>{
+pub fn execute(algorithm: &str, mode: String, key: String, tweak: String, input: String) -> Result<Vec<u8>, String> {
 
-    let key = de_encode_base64::decode(key).unwrap_or(vec![0; 32]);
-    
-    let key1: Vec<u8> = key[0..16].to_vec();
-    let key2: Vec<u8> = key[16..32].to_vec();
+    let key = de_encode_base64::decode(key).map_err(|e| format!("Invalid base64 in key: {}", e))?;
 
-    let tweak = de_encode_base64::decode(tweak).unwrap_or(vec![0; 16]);
-    let input= de_encode_base64::decode( input).unwrap_or(vec![0; 16]);
+    // The key is the concatenation of the data key and the tweak key, both of the algorithm's length
+    let length = aes_sea_128::key_length(algorithm).ok_or(format!("Unknown algorithm '{}'", algorithm))?;
+    if key.len() != 2 * length {
+        return Err(format!("Key of {} bytes does not match {}, which expects {} bytes", key.len(), algorithm, 2 * length));
+    }
+
+    let key1: Vec<u8> = key[..length].to_vec();
+    let key2: Vec<u8> = key[length..].to_vec();
+
+    let tweak = de_encode_base64::decode(tweak).map_err(|e| format!("Invalid base64 in tweak: {}", e))?;
+    let input = de_encode_base64::decode(input).map_err(|e| format!("Invalid base64 in input: {}", e))?;
 
     if input.is_empty() {
-       return input // If no input is given exit and return the empty string
+       return Ok(input) // If no input is given exit and return the empty string
     }
 
-    let tweak_encrypted = aes_sea_128::execute(&"sea128".to_string(), &String::from("encrypt"), &key2, tweak);
+    let tweak_encrypted = aes_sea_128::execute(algorithm, &String::from("encrypt"), &key2, tweak)?;
 
    match mode.as_str() {
-        "encrypt" => de_encrypt(mode, key1, tweak_encrypted, input),
-        "decrypt" => de_encrypt(mode, key1, tweak_encrypted, input),
-        _ => input
+        "encrypt" => de_encrypt(algorithm, mode, key1, tweak_encrypted, input),
+        "decrypt" => de_encrypt(algorithm, mode, key1, tweak_encrypted, input),
+        _ => Ok(input)
     }
 }
 
-fn de_encrypt(mode: String, key1: Vec<u8>, mut tweak_encrypted: Vec<u8>, input: Vec<u8>) -> Vec<u8> {
+fn de_encrypt(algorithm: &str, mode: String, key1: Vec<u8>, mut tweak_encrypted: Vec<u8>, input: Vec<u8>) -> Result<Vec<u8>, String> {
 
     let mut output: Vec<u8> = Vec::new();
     let alpha = vec![0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0];
@@ -39,7 +45,7 @@ fn de_encrypt(mode: String, key1: Vec<u8>, mut tweak_encrypted: Vec<u8>, input:
         }
 
         // En- or decrypt the chunk
-        chunk = aes_sea_128::execute(&"sea128".to_string(), &mode, &key1, chunk);
+        chunk = aes_sea_128::execute(algorithm, &mode, &key1, chunk)?;
 
         // XOR the chunk and the encrypted tweak
         for i in 0..chunk.len() {
@@ -52,7 +58,7 @@ fn de_encrypt(mode: String, key1: Vec<u8>, mut tweak_encrypted: Vec<u8>, input:
         // Multiply the tweak by alpha
         tweak_encrypted = gf_operations::gfmul( &"xex".to_string(), tweak_encrypted, alpha.clone());
     }
-    output
+    Ok(output)
 }
 
 #[cfg(test)]
@@ -67,7 +73,7 @@ mod tests {
 
         let expected = de_encode_base64::decode("mHAVhRCKPAPx0BcufG5BZ4+/CbneMV/gRvqK5rtLe0OJgpDU5iT7z2P0R7gEeRDO".to_string()).unwrap();
 
-        let result = execute("encrypt".to_string(), key, tweak, input);
+        let result = execute(&"sea128".to_string(), "encrypt".to_string(), key, tweak, input).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -80,7 +86,7 @@ mod tests {
         let expected = de_encode_base64::decode("SGV5IHdpZSBrcmFzcyBkYXMgZnVua3Rpb25pZXJ0IGphIG9mZmVuYmFyIGVjaHQu".to_string()).unwrap();
 
         
-        let result = execute("decrypt".to_string(), key, tweak, input);
+        let result = execute(&"sea128".to_string(), "decrypt".to_string(), key, tweak, input).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -93,7 +99,7 @@ mod tests {
         let expected = de_encode_base64::decode("".to_string()).unwrap();
 
         
-        let result = execute("encrypt".to_string(), key, tweak, input);
+        let result = execute(&"sea128".to_string(), "encrypt".to_string(), key, tweak, input).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -106,7 +112,39 @@ mod tests {
         let expected = de_encode_base64::decode("".to_string()).unwrap();
 
         
-        let result = execute("decrypt".to_string(), key, tweak, input);
+        let result = execute(&"sea128".to_string(), "decrypt".to_string(), key, tweak, input).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn encrypt_xts_aes256() {
+        // A 64 byte key is split into two AES-256 keys, expected value cross-checked with OpenSSL's XTS
+        let key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0+Pw==".to_string();
+        let tweak = "EBESExQVFhcYGRobHB0eHw==".to_string();
+        let input = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0+Pw==".to_string();
+
+        let expected = de_encode_base64::decode("GRijl5KouueRBGy3/zyUnOLVWc9VfXq4l+pRKnOeYgaf2ZSaQqI61zYN4Vw1JIWNUoEniTjvl+YdWf85Y6GKDw==".to_string()).unwrap();
+
+        let result = execute(&"aes256".to_string(), "encrypt".to_string(), key, tweak, input).unwrap();
         assert_eq!(result, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn key_length_mismatch() {
+        let key = "B1ygNO/CyRYIUYhTSgoUysX5Y/wWLi4UiWaVeloUWs0=".to_string();
+        let tweak = "6VXORr+YYHrd2nVe0OlA+Q==".to_string();
+        let input = "/aOg4jMocLkBLkDLgkHYtFKc2L9jjyd2WXSSyxXQikpMY9ZRnsJE76e9dW9olZIW".to_string();
+
+        assert!(execute(&"aes256".to_string(), "encrypt".to_string(), key, tweak, input).is_err());
+    }
+
+    #[test]
+    fn invalid_base64() {
+        let key = "B1ygNO/CyRYIUYhTSgoUysX5Y/wWLi4UiWaVeloUWs0=".to_string();
+        let tweak = "6VXORr+YYHrd2nVe0OlA+Q==".to_string();
+
+        let result = execute(&"sea128".to_string(), "encrypt".to_string(), "not base64!".to_string(), tweak.clone(), String::new());
+        assert!(result.unwrap_err().starts_with("Invalid base64 in key"));
+        assert!(execute(&"sea128".to_string(), "encrypt".to_string(), key, tweak, "!!!!".to_string()).is_err());
+    }
+}
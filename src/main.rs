@@ -3,289 +3,874 @@ mod actions;
 
 use serde_json::{json, Value};
 use std::env;
-use parser::TestCase;
+use rand::{rngs::StdRng, SeedableRng};
+use parser::{Polynomial, TestCase};
 use actions::*;
 
 fn main() {
-    // Collect the path from command-line arguments
-    let args: Vec<String> = env::args().collect();
-    let path = &args[1];
+    // Files are en- or decrypted by a separate command instead of a JSON file
+    if env::args().nth(1).as_deref() == Some("gcm-file") {
+        let output = gcm_file(env::args().skip(2).collect()).unwrap_or_else(|e| json!({"error": e}));
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        return;
+    }
+
+    // Collect the path and the optional flags from command-line arguments
+    let mut path: Option<String> = None;
+    let mut global_seed: Option<u64> = None;
+    let mut verbose = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let seed = args.next().expect("Missing value for --seed");
+                global_seed = Some(seed.parse().expect("Seed has to be an unsigned 64 bit integer"));
+            }
+            "--verbose" | "-v" => verbose = true,
+            _ => path = Some(arg)
+        }
+    }
+    let path = path.expect("Missing path to the JSON file");
 
     // Parse the JSON file into test cases
-    let test_cases = parser::parse_test_cases(path).expect("Failed to parse JSON");
+    let test_cases = parser::parse_test_cases(&path).expect("Failed to parse JSON");
     let mut responses = serde_json::Map::new();
 
     for (id, test_case) in test_cases {
-        let result = match test_case {
-            TestCase::add_numbers { number1, number2 } => {
-                let sum = add_numbers::execute(number1, number2);
-                json!({"sum": sum})
+        // Seed for the randomized actions: argument, then --seed, then a fresh one, reported so the run can be replayed
+        let seed_for = |seed: Option<u64>| -> u64 {
+            let seed = seed.or(global_seed).unwrap_or_else(rand::random);
+            if verbose {
+                eprintln!("{}: seed {}", id, seed);
             }
-            TestCase::sub_numbers { number1, number2 } => {
-                let difference = subtract_numbers::execute(number1, number2);
-                json!({"difference": difference})
-            }
-            TestCase::poly2block { semantic, coefficients } => {
-                let byte_vect = poly2byte::execute(&semantic, coefficients);
-                json!({"block": de_encode_base64::encode(byte_vect)}) // encoding byte_vect to base 64 String
-            }
-            TestCase::block2poly { semantic, block } => {
-                let coefficients = block2poly::execute(&semantic, block);
-                json!({"coefficients": coefficients})
-            }
-            TestCase::gfmul { semantic, a, b } => {
-                let a = de_encode_base64::decode( a).unwrap();
-                let b = de_encode_base64::decode( b).unwrap();
+            seed
+        };
 
-                let product = gf_operations::gfmul(&semantic, a, b);
-                json!({"product": de_encode_base64::encode(product)}) // encoding to base 64
-            }
-            TestCase::gfdiv { a, b } => {
-                let a = de_encode_base64::decode( a).unwrap();
-                let b = de_encode_base64::decode( b).unwrap();
+        let result = execute(test_case, &seed_for).unwrap_or_else(|e| json!({"error": e}));
 
-                let quotient = gf_operations::gfdiv(a, b);
-                json!({"q": de_encode_base64::encode(quotient)}) // encoding to base 64
-            }
-            TestCase::sea128 { mode, key, input } => {
-                let key = de_encode_base64::decode( key).unwrap();
-                let input = de_encode_base64::decode( input).unwrap();
+        // Add the result to the responses map with the ID as the key
+        responses.insert(id, result);
+    }
 
-                let output = aes_sea_128::execute(&"sea128".to_string(), &mode, &key, input);
-                json!({"output": de_encode_base64::encode(output)}) // encoding to base 64
-            }
-            TestCase::xex { mode, key, tweak, input } => {
-                let output = xex::execute(mode, key, tweak, input);
-                json!({"output": de_encode_base64::encode(output)}) // encoding to base 64
-            }
-            TestCase::gcm_encrypt { algorithm, nonce, key, plaintext, ad } => {
-                let nonce = de_encode_base64::decode(nonce).unwrap();
-                let key = de_encode_base64::decode(key).unwrap();
-                let plaintext = de_encode_base64::decode(plaintext).unwrap();
-                let ad = de_encode_base64::decode(ad).unwrap();
+    // Prepare and print the final JSON output
+    let output = json!({ "responses": responses });
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
 
-                let output = gcm::encrypt(algorithm, nonce, key, plaintext, ad);
-                json!({
-                    "ciphertext": de_encode_base64::encode(output.0),
-                    "tag": de_encode_base64::encode(output.1),
-                    "L": de_encode_base64::encode(output.2),
-                    "H": de_encode_base64::encode(output.3)
-                })
-            }
-            TestCase::gcm_decrypt { algorithm, nonce, key, ciphertext, ad, tag } => {
-                let nonce = de_encode_base64::decode(nonce).unwrap();
-                let key = de_encode_base64::decode(key).unwrap();
-                let ciphertext = de_encode_base64::decode(ciphertext).unwrap();
-                let ad = de_encode_base64::decode(ad).unwrap();
-                let tag = de_encode_base64::decode(tag).unwrap();
-                
-                let output = gcm::decrypt(algorithm, nonce, key, ciphertext, ad, tag);
+}
+
+// Runs a single testcase, an error only affects the response of this testcase
+fn execute(test_case: TestCase, seed_for: &dyn Fn(Option<u64>) -> u64) -> Result<Value, String> {
+    let result = match test_case {
+        TestCase::add_numbers { number1, number2 } => {
+            let sum = add_numbers::execute(number1, number2);
+            json!({"sum": sum})
+        }
+        TestCase::sub_numbers { number1, number2 } => {
+            let difference = subtract_numbers::execute(number1, number2);
+            json!({"difference": difference})
+        }
+        TestCase::poly2block { semantic, coefficients } => {
+            let byte_vect = poly2byte::execute(&semantic, coefficients);
+            json!({"block": de_encode_base64::encode(byte_vect)}) // encoding byte_vect to base 64 String
+        }
+        TestCase::block2poly { semantic, block } => {
+            let coefficients = block2poly::execute(&semantic, block);
+            json!({"coefficients": coefficients})
+        }
+        TestCase::gfmul { semantic, a, b } => {
+            let a = decode("a", a)?;
+            let b = decode("b", b)?;
+
+            let product = gf_operations::gfmul(&semantic, a, b);
+            json!({"product": de_encode_base64::encode(product)}) // encoding to base 64
+        }
+        TestCase::gfdiv { a, b } => {
+            let a = decode("a", a)?;
+            let b = decode("b", b)?;
+
+            let quotient = gf_operations::gfdiv(a, b);
+            json!({"q": de_encode_base64::encode(quotient)}) // encoding to base 64
+        }
+        TestCase::sea128 { mode, key, input } => {
+            let key = decode("key", key)?;
+            let input = decode("input", input)?;
+
+            let output = aes_sea_128::execute(&"sea128".to_string(), &mode, &key, input)?;
+            json!({"output": de_encode_base64::encode(output)}) // encoding to base 64
+        }
+        TestCase::xex { mode, key, tweak, input, algorithm } => {
+            // Without an algorithm XEX keeps using SEA-128 with a 32 byte key
+            let algorithm = algorithm.unwrap_or("sea128".to_string());
+
+            let output = xex::execute(&algorithm, mode, key, tweak, input)?;
+            json!({"output": de_encode_base64::encode(output)}) // encoding to base 64
+        }
+        TestCase::block_encrypt { algorithm, mode, padding, key, iv, plaintext } => {
+            let key = decode("key", key)?;
+            let iv = iv.map(|iv| decode("iv", iv)).transpose()?;
+            let plaintext = decode("plaintext", plaintext)?;
+            let padding = padding.unwrap_or(block_modes::default_padding(&mode).to_string());
+
+            let ciphertext = block_modes::encrypt(algorithm, mode, padding, key, iv, plaintext)?;
+            json!({"ciphertext": de_encode_base64::encode(ciphertext)})
+        }
+        TestCase::block_decrypt { algorithm, mode, padding, key, iv, ciphertext } => {
+            let key = decode("key", key)?;
+            let iv = iv.map(|iv| decode("iv", iv)).transpose()?;
+            let ciphertext = decode("ciphertext", ciphertext)?;
+            let padding = padding.unwrap_or(block_modes::default_padding(&mode).to_string());
+
+            let plaintext = block_modes::decrypt(algorithm, mode, padding, key, iv, ciphertext)?;
+            json!({"plaintext": de_encode_base64::encode(plaintext)})
+        }
+        TestCase::gcm_encrypt { algorithm, nonce, key, plaintext, ad, tag_length } => {
+            let nonce = decode("nonce", nonce)?;
+            let key = decode("key", key)?;
+            let plaintext = decode("plaintext", plaintext)?;
+            let ad = decode("ad", ad)?;
+
+            let output = gcm::encrypt(algorithm, nonce, key, plaintext, ad, tag_length.unwrap_or(16))?;
+            json!({
+                "ciphertext": de_encode_base64::encode(output.0),
+                "tag": de_encode_base64::encode(output.1),
+                "L": de_encode_base64::encode(output.2),
+                "H": de_encode_base64::encode(output.3)
+            })
+        }
+        TestCase::gcm_decrypt { algorithm, nonce, key, ciphertext, ad, tag, tag_length, diagnostic } => {
+            let nonce = decode("nonce", nonce)?;
+            let key = decode("key", key)?;
+            let ciphertext = decode("ciphertext", ciphertext)?;
+            let ad = decode("ad", ad)?;
+            let tag = decode("tag", tag)?;
+            
+            let tag_length = tag_length.unwrap_or(16);
+
+            // The diagnostic mode hands out the plaintext even if the tag does not match
+            if diagnostic.unwrap_or(false) {
+                let output = gcm::decrypt_diagnostic(algorithm, nonce, key, ciphertext, ad, tag, tag_length)?;
                 json!({
                     "authentic": output.0,
                     "plaintext": de_encode_base64::encode(output.1)
                 })
+            } else {
+                aead_decrypt_response(gcm::decrypt(algorithm, nonce, key, ciphertext, ad, tag, tag_length))
             }
-            TestCase::padding_oracle { hostname, port, iv, ciphertext } => {
-                let iv = de_encode_base64::decode(iv).unwrap();
-                let ciphertext = de_encode_base64::decode(ciphertext).unwrap();
-                
-                let plaintext = padding_oracle::execute(hostname, port, iv, ciphertext).unwrap();
-                json!({
-                    "plaintext": de_encode_base64::encode(plaintext)
-                })
-            }
-            TestCase::gfpoly_add { A, B } => {
-                let a = de_encode_base64::decode_vectors(A);
-                let b = de_encode_base64::decode_vectors(B);
-                
-                let summ = gfpoly_operations::add(&a, &b);
+        }
+        TestCase::gcm_siv_encrypt { algorithm, nonce, key, plaintext, ad } => {
+            let nonce = decode("nonce", nonce)?;
+            let key = decode("key", key)?;
+            let plaintext = decode("plaintext", plaintext)?;
+            let ad = decode("ad", ad)?;
+
+            let (ciphertext, tag) = gcm_siv::encrypt(algorithm, nonce, key, plaintext, ad)?;
+            json!({
+                "ciphertext": de_encode_base64::encode(ciphertext),
+                "tag": de_encode_base64::encode(tag)
+            })
+        }
+        TestCase::gcm_siv_decrypt { algorithm, nonce, key, ciphertext, ad, tag } => {
+            let nonce = decode("nonce", nonce)?;
+            let key = decode("key", key)?;
+            let ciphertext = decode("ciphertext", ciphertext)?;
+            let ad = decode("ad", ad)?;
+            let tag = decode("tag", tag)?;
+
+            aead_decrypt_response(gcm_siv::decrypt(algorithm, nonce, key, ciphertext, ad, tag))
+        }
+        TestCase::chacha20poly1305_encrypt { nonce, key, plaintext, ad } => {
+            let nonce = decode("nonce", nonce)?;
+            let key = decode("key", key)?;
+            let plaintext = decode("plaintext", plaintext)?;
+            let ad = decode("ad", ad)?;
+
+            let output = chacha20poly1305::encrypt(nonce, key, plaintext, ad)?;
+            json!({
+                "ciphertext": de_encode_base64::encode(output.0),
+                "tag": de_encode_base64::encode(output.1),
+                "poly1305_key": de_encode_base64::encode(output.2),
+                "r": de_encode_base64::encode(output.3),
+                "s": de_encode_base64::encode(output.4)
+            })
+        }
+        TestCase::chacha20poly1305_decrypt { nonce, key, ciphertext, ad, tag, diagnostic } => {
+            let nonce = decode("nonce", nonce)?;
+            let key = decode("key", key)?;
+            let ciphertext = decode("ciphertext", ciphertext)?;
+            let ad = decode("ad", ad)?;
+            let tag = decode("tag", tag)?;
+
+            // The diagnostic mode hands out the plaintext and the Poly1305 values even if the tag does not match
+            if diagnostic.unwrap_or(false) {
+                let output = chacha20poly1305::decrypt_diagnostic(nonce, key, ciphertext, ad, tag)?;
                 json!({
-                    "S": de_encode_base64::encode_vectors(summ)
+                    "authentic": output.0,
+                    "plaintext": de_encode_base64::encode(output.1),
+                    "poly1305_key": de_encode_base64::encode(output.2),
+                    "r": de_encode_base64::encode(output.3),
+                    "s": de_encode_base64::encode(output.4)
                 })
+            } else {
+                aead_decrypt_response(chacha20poly1305::decrypt(nonce, key, ciphertext, ad, tag))
             }
-            TestCase::gfpoly_mul { A, B } => {
-                let a = de_encode_base64::decode_vectors(A);
-                let b = de_encode_base64::decode_vectors(B);
-                
-                let product = gfpoly_operations::mul(&a, &b);
-                json!({
-                    "P": de_encode_base64::encode_vectors(product)
-                })
+        }
+        TestCase::ccm_encrypt { algorithm, nonce, key, plaintext, ad, tag_length } => {
+            let nonce = decode("nonce", nonce)?;
+            let key = decode("key", key)?;
+            let plaintext = decode("plaintext", plaintext)?;
+            let ad = decode("ad", ad)?;
+
+            let (ciphertext, tag) = ccm::encrypt(algorithm, nonce, key, plaintext, ad, tag_length.unwrap_or(16))?;
+            json!({
+                "ciphertext": de_encode_base64::encode(ciphertext),
+                "tag": de_encode_base64::encode(tag)
+            })
+        }
+        TestCase::ccm_decrypt { algorithm, nonce, key, ciphertext, ad, tag, tag_length } => {
+            let nonce = decode("nonce", nonce)?;
+            let key = decode("key", key)?;
+            let ciphertext = decode("ciphertext", ciphertext)?;
+            let ad = decode("ad", ad)?;
+            let tag = decode("tag", tag)?;
+
+            aead_decrypt_response(ccm::decrypt(algorithm, nonce, key, ciphertext, ad, tag, tag_length.unwrap_or(16)))
+        }
+        TestCase::eax_encrypt { algorithm, nonce, key, plaintext, ad, tag_length } => {
+            let nonce = decode("nonce", nonce)?;
+            let key = decode("key", key)?;
+            let plaintext = decode("plaintext", plaintext)?;
+            let ad = decode("ad", ad)?;
+
+            let (ciphertext, tag) = eax::encrypt(algorithm, nonce, key, plaintext, ad, tag_length.unwrap_or(16))?;
+            json!({
+                "ciphertext": de_encode_base64::encode(ciphertext),
+                "tag": de_encode_base64::encode(tag)
+            })
+        }
+        TestCase::eax_decrypt { algorithm, nonce, key, ciphertext, ad, tag, tag_length } => {
+            let nonce = decode("nonce", nonce)?;
+            let key = decode("key", key)?;
+            let ciphertext = decode("ciphertext", ciphertext)?;
+            let ad = decode("ad", ad)?;
+            let tag = decode("tag", tag)?;
+
+            aead_decrypt_response(eax::decrypt(algorithm, nonce, key, ciphertext, ad, tag, tag_length.unwrap_or(16)))
+        }
+        TestCase::gmac { algorithm, nonce, key, data, tag_length, diagnostic } => {
+            let nonce = decode("nonce", nonce)?;
+            let key = decode("key", key)?;
+            let data = decode("data", data)?;
+
+            let (tag, h, y0_encrypted, l) = gmac::compute(algorithm, nonce, key, data, tag_length.unwrap_or(16))?;
+            let mut output = json!({
+                "tag": de_encode_base64::encode(tag)
+            });
+            // Diagnostic mode additionally reports the intermediate values like gcm_encrypt
+            if diagnostic.unwrap_or(false) {
+                output["H"] = json!(de_encode_base64::encode(h));
+                output["J0_encrypted"] = json!(de_encode_base64::encode(y0_encrypted));
+                output["L"] = json!(de_encode_base64::encode(l));
             }
-            TestCase::gfpoly_pow { A, k } => {
-                let a = de_encode_base64::decode_vectors(A);
-                
-                let power = gfpoly_operations::pow(&a, k);
+            output
+        }
+        TestCase::gmac_verify { algorithm, nonce, key, data, tag, tag_length, diagnostic } => {
+            let nonce = decode("nonce", nonce)?;
+            let key = decode("key", key)?;
+            let data = decode("data", data)?;
+            let tag = decode("tag", tag)?;
 
-                json!({
-                    "Z": de_encode_base64::encode_vectors(power)
-                })
+            let (authentic, (_, h, y0_encrypted, l)) = gmac::verify(algorithm, nonce, key, data, tag, tag_length.unwrap_or(16))?;
+            let mut output = json!({
+                "authentic": authentic
+            });
+            if diagnostic.unwrap_or(false) {
+                output["H"] = json!(de_encode_base64::encode(h));
+                output["J0_encrypted"] = json!(de_encode_base64::encode(y0_encrypted));
+                output["L"] = json!(de_encode_base64::encode(l));
             }
-            TestCase::gfpoly_divmod { A, B } => {
-                let a = de_encode_base64::decode_vectors(A);
-                let b = de_encode_base64::decode_vectors(B);
-                
-                let (q, r) = gfpoly_operations::divmod(&a, &b);
+            output
+        }
+        TestCase::padding_oracle { hostname, port, iv, ciphertext } => {
+            let iv = decode("iv", iv)?;
+            let ciphertext = decode("ciphertext", ciphertext)?;
+            
+            let plaintext = padding_oracle::execute(hostname, port, iv, ciphertext).map_err(|e| format!("Padding oracle failed: {}", e))?;
+            json!({
+                "plaintext": de_encode_base64::encode(plaintext)
+            })
+        }
+        TestCase::gfpoly_add { A, B, format } => {
+            let a = A.decode()?;
+            let b = B.decode()?;
+            
+            let summ = gfpoly_operations::add(&a, &b);
+            json!({
+                "S": poly_notation::encode_poly(summ, &format)
+            })
+        }
+        TestCase::gfpoly_mul { A, B, format } => {
+            let a = A.decode()?;
+            let b = B.decode()?;
+            
+            let product = gfpoly_operations::mul(&a, &b);
+            json!({
+                "P": poly_notation::encode_poly(product, &format)
+            })
+        }
+        TestCase::gfpoly_pow { A, k, format } => {
+            let a = A.decode()?;
+            
+            let power = gfpoly_operations::pow(&a, k);
 
-                json!({
-                    "Q": de_encode_base64::encode_vectors(q),
-                    "R": de_encode_base64::encode_vectors(r)
-                })
+            json!({
+                "Z": poly_notation::encode_poly(power, &format)
+            })
+        }
+        TestCase::gfpoly_divmod { A, B, format } => {
+            let a = A.decode()?;
+            let b = B.decode()?;
+            
+            let (q, r) = gfpoly_operations::divmod(&a, &b);
+
+            json!({
+                "Q": poly_notation::encode_poly(q, &format),
+                "R": poly_notation::encode_poly(r, &format)
+            })
+        }
+        TestCase::gfpoly_powmod { A, M, k, format } => {
+            let a = A.decode()?;
+            let m = M.decode()?;
+            
+            let power = gfpoly_operations::powmod(&a, &m, k);
+
+            json!({
+                "Z": poly_notation::encode_poly(power, &format)
+            })
+        }
+        TestCase::gfpoly_sort { polys, format } => {
+            let mut input: Vec<Vec<Vec<u8>>> = Vec::new();
+            let mut sorted: Vec<Value> = Vec::new();
+
+            for poly in polys {
+                input.push(poly.decode()?);
             }
-            TestCase::gfpoly_powmod { A, M, k } => {
-                let a = de_encode_base64::decode_vectors(A);
-                let m = de_encode_base64::decode_vectors(M);
-                
-                let power = gfpoly_operations::powmod(&a, &m, k);
 
-                json!({
-                    "Z": de_encode_base64::encode_vectors(power)
-                })
+            input = gfpoly_operations::sort(input);
+
+            for poly in input {
+                sorted.push(poly_notation::encode_poly(poly, &format));
             }
-            TestCase::gfpoly_sort { polys } => {
-                let mut input: Vec<Vec<Vec<u8>>> = Vec::new();
-                let mut sorted: Vec<Vec<String>> = Vec::new();
+            json!({
+                "sorted_polys": sorted
+            })
+        }
+        TestCase::gfpoly_make_monic { A, format } => {
+            let mut a = A.decode()?;
+            
+            a = gfpoly_operations::make_monic(&a);
 
-                for poly in polys {
-                    input.push(de_encode_base64::decode_vectors(poly));
-                }
+            json!({
+                "A*": poly_notation::encode_poly(a, &format)
+            })
+        }
+        TestCase::gfpoly_sqrt { Q, format } => {
+            let mut q = Q.decode()?;
+            
+            q = gfpoly_operations::sqrt(&q);
 
-                input = gfpoly_operations::sort(input);
+            json!({
+                "S": poly_notation::encode_poly(q, &format)
+            })
+        }
+        TestCase::gfpoly_diff { F, format } => {
+            let mut f = F.decode()?;
+            
+            f = gfpoly_operations::diff(f);
 
-                for poly in input {
-                    sorted.push(de_encode_base64::encode_vectors(poly));
-                }
-                json!({
-                    "sorted_polys": sorted
-                })
-            }
-            TestCase::gfpoly_make_monic { A } => {
-                let mut a = de_encode_base64::decode_vectors(A);
-                
-                a = gfpoly_operations::make_monic(&a);
+            json!({
+                "F'": poly_notation::encode_poly(f, &format)
+            })
+        }
+        TestCase::gfpoly_gcd { A, B, format } => {
+            let a = A.decode()?;
+            let b = B.decode()?;
+            
+            let product = gfpoly_operations::gcd(&a, &b);
+            json!({
+                "G": poly_notation::encode_poly(product, &format)
+            })
+        }
+        TestCase::gfpoly_xgcd { A, B, format } => {
+            let a = A.decode()?;
+            let b = B.decode()?;
 
-                json!({
-                    "A*": de_encode_base64::encode_vectors(a)
+            let (g, u, v) = gfpoly_operations::xgcd(&a, &b);
+            json!({
+                "G": poly_notation::encode_poly(g, &format),
+                "U": poly_notation::encode_poly(u, &format),
+                "V": poly_notation::encode_poly(v, &format)
+            })
+        }
+        TestCase::gfpoly_compose { F, G, format } => {
+            let f = F.decode()?;
+            let g = G.decode()?;
+
+            let composition = gfpoly_operations::compose(&f, &g);
+            json!({
+                "C": poly_notation::encode_poly(composition, &format)
+            })
+        }
+        TestCase::gfpoly_compose_mod { F, G, M, format } => {
+            let f = F.decode()?;
+            let g = G.decode()?;
+            let m = M.decode()?;
+
+            let composition = gfpoly_operations::compose_mod(&f, &g, &m)?;
+            json!({
+                "C": poly_notation::encode_poly(composition, &format)
+            })
+        }
+        TestCase::gfpoly_invmod { A, M, format } => {
+            let a = A.decode()?;
+            let m = M.decode()?;
+
+            match gfpoly_operations::invmod(&a, &m)? {
+                Some(inverse) => json!({
+                    "invertible": true,
+                    "I": poly_notation::encode_poly(inverse, &format)
+                }),
+                None => json!({
+                    "invertible": false
                 })
             }
-            TestCase::gfpoly_sqrt { Q } => {
-                let mut q = de_encode_base64::decode_vectors(Q);
-                
-                q = gfpoly_operations::sqrt(&q);
+        }
+        TestCase::gfpoly_crt { residues, moduli, format } => {
+            let residues: Vec<Vec<Vec<u8>>> = residues.into_iter().map(Polynomial::decode).collect::<Result<_, _>>()?;
+            let moduli: Vec<Vec<Vec<u8>>> = moduli.into_iter().map(Polynomial::decode).collect::<Result<_, _>>()?;
 
-                json!({
-                    "S": de_encode_base64::encode_vectors(q)
+            let (r, m) = gfpoly_operations::crt(&residues, &moduli)?;
+            json!({
+                "R": poly_notation::encode_poly(r, &format),
+                "M": poly_notation::encode_poly(m, &format)
+            })
+        }
+        TestCase::gfpoly_resultant { A, B, format } => {
+            let a = A.decode()?;
+            let b = B.decode()?;
+
+            let resultant = gfpoly_operations::resultant(&a, &b);
+            json!({
+                "R": poly_notation::encode_element(resultant, &format)
+            })
+        }
+        TestCase::gfpoly_discriminant { F, format } => {
+            let f = F.decode()?;
+
+            let discriminant = gfpoly_operations::discriminant(&f);
+            json!({
+                "D": poly_notation::encode_element(discriminant, &format)
+            })
+        }
+        TestCase::gfpoly_factor_sff { F, format } => {
+            let factors = F.decode()?;
+
+            let results: Vec<Value> = gfpoly_operations::sff(&factors)
+                .into_iter()
+                .map(|(f, e)| {
+                    json!({
+                        "factor": poly_notation::encode_poly(f, &format),
+                        "exponent": e
+                    })
                 })
-            }
-            TestCase::gfpoly_diff { F } => {
-                let mut f = de_encode_base64::decode_vectors(F);
-                
-                f = gfpoly_operations::diff(f);
+                .collect();
 
-                json!({
-                    "F'": de_encode_base64::encode_vectors(f)
+            json!({
+                "factors": results
+            })
+        }
+        TestCase::gfpoly_factor_ddf { F, algorithm, format } => {
+            let factors = F.decode()?;
+
+            let ddf = match algorithm.as_deref() {
+                None | Some("ddf") => gfpoly_operations::ddf(&factors),
+                Some("bsgs") => gfpoly_operations::ddf_bsgs(&factors),
+                Some(algorithm) => return Err(format!("Unknown algorithm '{}', expected 'ddf' or 'bsgs'", algorithm))
+            };
+
+            let results: Vec<Value> = ddf
+                .into_iter()
+                .map(|(f, d)| {
+                    json!({
+                        "factor": poly_notation::encode_poly(f, &format),
+                        "degree": d
+                    })
                 })
-            }
-            TestCase::gfpoly_gcd { A, B } => {
-                let a = de_encode_base64::decode_vectors(A);
-                let b = de_encode_base64::decode_vectors(B);
-                
-                let product = gfpoly_operations::gcd(&a, &b);
-                json!({
-                    "G": de_encode_base64::encode_vectors(product)
+                .collect();
+
+            json!({
+                "factors": results
+            })
+        }
+        TestCase::gfpoly_factor_edf { F, d, algorithm, seed, format } => {
+            let f = F.decode()?;
+            
+            // Berlekamp finds the degrees itself, equal-degree factorization needs them given
+            let factors = match (algorithm.as_deref(), d) {
+                (Some("berlekamp"), d) => {
+                    let factors = gfpoly_operations::berlekamp(&f);
+                    if let Some(d) = d.filter(|&d| factors.iter().any(|factor| gfpoly_operations::degree(factor) as u128 != d)) {
+                        return Err(format!("Not all factors have degree {}", d));
+                    }
+                    factors
+                }
+                (None | Some("cantor_zassenhaus" | "edf"), Some(d)) => {
+                    let mut rng = StdRng::seed_from_u64(seed_for(seed));
+                    gfpoly_operations::edf(&f, d as usize, &mut rng)
+                }
+                (None | Some("cantor_zassenhaus" | "edf"), None) => return Err("Equal-degree factorization needs the factor degree d".to_string()),
+                (Some(algorithm), _) => return Err(format!("Unknown algorithm '{}', expected 'berlekamp', 'cantor_zassenhaus' or 'edf'", algorithm))
+            };
+
+            let encoded_factors: Vec<Value> = factors
+                .iter()
+                .map(|factor| poly_notation::encode_poly(factor.clone(), &format))
+                .collect();
+
+            json!({
+                "factors": encoded_factors
+            })
+        }
+        TestCase::gfpoly_factor { F, algorithm, format } => {
+            let f = F.decode()?;
+            let algorithm = algorithm.unwrap_or("cantor_zassenhaus".to_string());
+
+            let mut rng = StdRng::seed_from_u64(seed_for(None));
+
+            let (leading_coefficient, factors) = gfpoly_operations::factor(&f, &algorithm, &mut rng)?;
+            let results: Vec<Value> = factors
+                .into_iter()
+                .map(|(f, e)| {
+                    json!({
+                        "factor": poly_notation::encode_poly(f, &format),
+                        "exponent": e
+                    })
                 })
-            }
-            TestCase::gfpoly_factor_sff { F } => {
-                let factors = de_encode_base64::decode_vectors(F);
+                .collect();
+
+            json!({
+                "leading_coefficient": poly_notation::encode_element(leading_coefficient, &format),
+                "factors": results
+            })
+        }
+        TestCase::gfpoly_is_irreducible { F } => {
+            let f = F.decode()?;
+
+            json!({
+                "irreducible": gfpoly_operations::is_irreducible(&f)
+            })
+        }
+        TestCase::gfpoly_random_irreducible { degree, seed, format } => {
+            let seed = seed_for(seed);
+
+            let f = gfpoly_operations::random_irreducible(degree, seed)?;
+            json!({
+                "F": poly_notation::encode_poly(f, &format),
+                "seed": seed
+            })
+        }
+        TestCase::gfpoly_roots { F, multiplicities, format } => {
+            let f = F.decode()?;
+            let mut rng = StdRng::seed_from_u64(seed_for(None));
 
-                let results: Vec<Value> = gfpoly_operations::sff(&factors)
+            if multiplicities.unwrap_or(false) {
+                let results: Vec<Value> = gfpoly_operations::roots_multiplicity(&f, &mut rng)
                     .into_iter()
-                    .map(|(f, e)| {
+                    .map(|(root, e)| {
                         json!({
-                            "factor": de_encode_base64::encode_vectors(f),
-                            "exponent": e
+                            "root": poly_notation::encode_element(root, &format),
+                            "multiplicity": e
                         })
                     })
                     .collect();
 
                 json!({
-                    "factors": results
+                    "roots": results
                 })
-            }
-            TestCase::gfpoly_factor_ddf { F } => {
-                let factors = de_encode_base64::decode_vectors(F);
-
-                let results: Vec<Value> = gfpoly_operations::ddf(&factors)
+            } else {
+                let roots: Vec<Value> = gfpoly_operations::roots(&f, &mut rng)
                     .into_iter()
-                    .map(|(f, d)| {
-                        json!({
-                            "factor": de_encode_base64::encode_vectors(f),
-                            "degree": d
-                        })
-                    })
+                    .map(|root| poly_notation::encode_element(root, &format))
                     .collect();
 
                 json!({
-                    "factors": results
+                    "roots": roots
                 })
             }
-            TestCase::gfpoly_factor_edf { F, d } => {
-                let f = de_encode_base64::decode_vectors(F);
-                
-                let result = gfpoly_operations::edf(&f, d as usize);
-
-                let encoded_factors: Vec<Vec<String>> = result
-                    .iter()
-                    .map(|factor| de_encode_base64::encode_vectors(factor.clone()))
-                    .collect();
+        }
+        TestCase::berlekamp_massey { sequence, bits, format } => {
+            // A bit sequence is given as plain 0/1 values, field elements as base64 blocks
+            let (c, l) = match (sequence, bits) {
+                (Some(sequence), None) => berlekamp_massey::execute(decode_vectors("sequence", sequence)?),
+                (None, Some(bits)) => berlekamp_massey::execute_bits(bits)?,
+                (None, None) => return Err("Either sequence or bits is required".to_string()),
+                (Some(_), Some(_)) => return Err("Only one of sequence and bits may be given".to_string())
+            };
 
-                json!({
-                    "factors": encoded_factors
+            json!({
+                "C": poly_notation::encode_poly(c, &format),
+                "L": l
+            })
+        }
+        TestCase::gfmatrix_rref { A } => {
+            let a: Vec<Vec<Vec<u8>>> = A.into_iter().map(|rows| decode_vectors("A", rows)).collect::<Result<_, _>>()?;
+
+            let (reduced, pivots) = gf_matrix::rref(&a)?;
+            json!({
+                "R": reduced.into_iter().map(de_encode_base64::encode_vectors).collect::<Vec<_>>(),
+                "pivots": pivots
+            })
+        }
+        TestCase::gfmatrix_rank { A } => {
+            let a: Vec<Vec<Vec<u8>>> = A.into_iter().map(|rows| decode_vectors("A", rows)).collect::<Result<_, _>>()?;
+
+            let rank = gf_matrix::rank(&a)?;
+            json!({
+                "rank": rank
+            })
+        }
+        TestCase::gfmatrix_det { A } => {
+            let a: Vec<Vec<Vec<u8>>> = A.into_iter().map(|rows| decode_vectors("A", rows)).collect::<Result<_, _>>()?;
+
+            let det = gf_matrix::determinant(&a)?;
+            json!({
+                "det": de_encode_base64::encode(det)
+            })
+        }
+        TestCase::gfmatrix_inverse { A } => {
+            let a: Vec<Vec<Vec<u8>>> = A.into_iter().map(|rows| decode_vectors("A", rows)).collect::<Result<_, _>>()?;
+
+            match gf_matrix::inverse(&a) {
+                Ok(inverse) => json!({
+                    "invertible": true,
+                    "I": inverse.into_iter().map(de_encode_base64::encode_vectors).collect::<Vec<_>>()
+                }),
+                Err(e) => json!({
+                    "invertible": false,
+                    "error": e
                 })
             }
-            TestCase::gcm_crack { nonce, m1, m2, m3, forgery } => {
-                
-                let nonce = de_encode_base64::decode(nonce).unwrap();
-
-                // Decode m1 fields and create tuple
-                let m1_ciphertext = de_encode_base64::decode(m1.ciphertext).unwrap();
-                let m1_associated_data = de_encode_base64::decode(m1.associated_data).unwrap();
-                let m1_tag = de_encode_base64::decode(m1.tag).unwrap();
-                let m1 = (m1_ciphertext, m1_associated_data, m1_tag);
-        
-                // Decode m2 fields and create tuple
-                let m2_ciphertext = de_encode_base64::decode(m2.ciphertext).unwrap();
-                let m2_associated_data = de_encode_base64::decode(m2.associated_data).unwrap();
-                let m2_tag = de_encode_base64::decode(m2.tag).unwrap();
-                let m2 = (m2_ciphertext, m2_associated_data, m2_tag);
-        
-                // Decode m3 fields and create tuple
-                let m3_ciphertext = de_encode_base64::decode(m3.ciphertext).unwrap();
-                let m3_associated_data = de_encode_base64::decode(m3.associated_data).unwrap();
-                let m3_tag = de_encode_base64::decode(m3.tag).unwrap();
-                let m3 = (m3_ciphertext, m3_associated_data, m3_tag);
-        
-                // Decode forgery fields and create tuple
-                let forgery_ciphertext = de_encode_base64::decode(forgery.ciphertext).unwrap();
-                let forgery_associated_data = de_encode_base64::decode(forgery.associated_data).unwrap();
-                let forgery = (forgery_ciphertext, forgery_associated_data);
-
-                let (tag, h, mask) = gcm_crack::execute(nonce, m1, m2, m3, forgery);
+        }
+        TestCase::gfmatrix_solve { A, b } => {
+            let a: Vec<Vec<Vec<u8>>> = A.into_iter().map(|rows| decode_vectors("A", rows)).collect::<Result<_, _>>()?;
+            let b = decode_vectors("b", b)?;
 
-                json!({
-                    "tag": de_encode_base64::encode(tag),
-                    "H": de_encode_base64::encode(h),
-                    "mask": de_encode_base64::encode(mask)
+            match gf_matrix::solve(&a, &b) {
+                Ok(x) => json!({
+                    "solvable": true,
+                    "x": de_encode_base64::encode_vectors(x)
+                }),
+                Err(e) => json!({
+                    "solvable": false,
+                    "error": e
                 })
             }
-        };
+        }
+        TestCase::gfmatrix_nullspace { A } => {
+            let a: Vec<Vec<Vec<u8>>> = A.into_iter().map(|rows| decode_vectors("A", rows)).collect::<Result<_, _>>()?;
 
-        // Add the result to the responses map with the ID as the key
-        responses.insert(id, result);
+            let basis = gf_matrix::nullspace(&a)?;
+            json!({
+                "basis": basis.into_iter().map(de_encode_base64::encode_vectors).collect::<Vec<_>>()
+            })
+        }
+        TestCase::bitmatrix_build { semantic, map, c, k } => {
+            // Either x -> c * x or x -> x^(2^k), as 128 rows of 16 bytes
+            let matrix = match map.as_str() {
+                "mul" => {
+                    let c = decode("c", c.ok_or("Missing argument c")?)?;
+                    if c.len() != 16 {
+                        return Err(format!("Constant c must be 16 bytes, not {}", c.len()));
+                    }
+                    bitmatrix::mul_constant(&c, &semantic)
+                }
+                "frobenius" => bitmatrix::frobenius(k.unwrap_or(1), &semantic),
+                _ => return Err(format!("Unknown map '{}'", map))
+            };
+
+            json!({
+                "M": de_encode_base64::encode_vectors(matrix)
+            })
+        }
+        TestCase::bitmatrix_apply { M, x } => {
+            let matrix = decode_vectors("M", M)?;
+            let x = decode("x", x)?;
+
+            json!({
+                "y": de_encode_base64::encode(bitmatrix::mul_vector(&matrix, &x))
+            })
+        }
+        TestCase::bitmatrix_kernel { M, columns } => {
+            let matrix = decode_vectors("M", M)?;
+            let columns = columns.unwrap_or(matrix.first().map_or(0, |row| row.len() * 8));
+            bitmatrix::check_columns(&matrix, columns)?;
+
+            json!({
+                "rank": bitmatrix::rank(&matrix, columns),
+                "basis": de_encode_base64::encode_vectors(bitmatrix::kernel(&matrix, columns))
+            })
+        }
+        TestCase::gf2poly_factor { F } => {
+            // Binary polynomials are given and returned as their exponents, like in poly2block
+            let f = gf2poly::from_exponents(&F);
+
+            let factors: Vec<Value> = gf2poly::factor(&f)
+                .into_iter()
+                .map(|(factor, exponent)| json!({
+                    "factor": gf2poly::to_exponents(&factor),
+                    "exponent": exponent
+                }))
+                .collect();
+
+            json!({
+                "factors": factors
+            })
+        }
+        TestCase::gf2poly_is_irreducible { F } => {
+            let f = gf2poly::from_exponents(&F);
+
+            json!({
+                "irreducible": gf2poly::is_irreducible(&f)
+            })
+        }
+        TestCase::gf2poly_is_primitive { F } => {
+            let f = gf2poly::from_exponents(&F);
+
+            let primitive = gf2poly::is_primitive(&f)?;
+            json!({
+                "primitive": primitive
+            })
+        }
+        TestCase::gcm_crack { nonce, m1, m2, m3, forgery, seed } => {
+            
+            let nonce = decode("nonce", nonce)?;
+
+            // Decode m1 fields and create tuple
+            let m1_ciphertext = decode("m1.ciphertext", m1.ciphertext)?;
+            let m1_associated_data = decode("m1.associated_data", m1.associated_data)?;
+            let m1_tag = decode("m1.tag", m1.tag)?;
+            let m1 = (m1_ciphertext, m1_associated_data, m1_tag);
+    
+            // Decode m2 fields and create tuple
+            let m2_ciphertext = decode("m2.ciphertext", m2.ciphertext)?;
+            let m2_associated_data = decode("m2.associated_data", m2.associated_data)?;
+            let m2_tag = decode("m2.tag", m2.tag)?;
+            let m2 = (m2_ciphertext, m2_associated_data, m2_tag);
+    
+            // Decode m3 fields and create tuple
+            let m3_ciphertext = decode("m3.ciphertext", m3.ciphertext)?;
+            let m3_associated_data = decode("m3.associated_data", m3.associated_data)?;
+            let m3_tag = decode("m3.tag", m3.tag)?;
+            let m3 = (m3_ciphertext, m3_associated_data, m3_tag);
+    
+            // Decode forgery fields and create tuple
+            let forgery_ciphertext = decode("forgery.ciphertext", forgery.ciphertext)?;
+            let forgery_associated_data = decode("forgery.associated_data", forgery.associated_data)?;
+            let forgery = (forgery_ciphertext, forgery_associated_data);
+
+            let mut rng = StdRng::seed_from_u64(seed_for(seed));
+
+            let (tag, h, mask) = gcm_crack::execute(nonce, m1, m2, m3, forgery, &mut rng);
+
+            json!({
+                "tag": de_encode_base64::encode(tag),
+                "H": de_encode_base64::encode(h),
+                "mask": de_encode_base64::encode(mask)
+            })
+        }
+    };
+    Ok(result)
+}
+
+// Base64 argument of a testcase, a malformed one is an error of the testcase
+fn decode(name: &str, value: String) -> Result<Vec<u8>, String> {
+    de_encode_base64::decode(value).map_err(|e| format!("Invalid base64 in {}: {}", name, e))
+}
+
+fn decode_vectors(name: &str, values: Vec<String>) -> Result<Vec<Vec<u8>>, String> {
+    values.into_iter().map(|value| decode(name, value)).collect()
+}
+
+// A failed tag check is reported as not authentic, any other error as is
+fn aead_decrypt_response(result: Result<Vec<u8>, aead::AeadError>) -> Value {
+    match result {
+        Ok(plaintext) => json!({
+            "authentic": true,
+            "plaintext": de_encode_base64::encode(plaintext)
+        }),
+        Err(aead::AeadError::AuthenticationFailed) => json!({
+            "authentic": false,
+            "error": aead::AeadError::AuthenticationFailed.to_string()
+        }),
+        Err(e) => json!({
+            "error": e.to_string()
+        })
     }
+}
 
-    // Prepare and print the final JSON output
-    let output = json!({ "responses": responses });
-    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+// kauma gcm-file <encrypt|decrypt> --algorithm A --key K --nonce N [--ad AD] [--tag T] [--tag-length L] <input> <output>
+// Key, nonce, AD and tag are base64, the output mirrors gcm_encrypt and gcm_decrypt without the text itself
+fn gcm_file(args: Vec<String>) -> Result<Value, String> {
+    let mut options = std::collections::HashMap::new();
+    let mut positional = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--") {
+            Some(name) => {
+                let value = args.next().ok_or(format!("Missing value for --{}", name))?;
+                options.insert(name.to_string(), value);
+            }
+            None => positional.push(arg)
+        }
+    }
+
+    let [mode, input, output] = positional.as_slice() else {
+        return Err("Usage: kauma gcm-file <encrypt|decrypt> --algorithm A --key K --nonce N [--ad AD] [--tag T] [--tag-length L] <input> <output>".to_string());
+    };
+    let decode = |name: &str| {
+        de_encode_base64::decode(options.get(name).cloned().unwrap_or_default()).map_err(|e| format!("Invalid base64 in --{}: {}", name, e))
+    };
+    let tag_length = options
+        .get("tag-length")
+        .map(|length| length.parse::<usize>().map_err(|_| format!("Tag length '{}' has to be a number", length)))
+        .transpose()?;
+
+    let algorithm = options.get("algorithm").cloned().unwrap_or("aes128".to_string());
+    let key = decode("key")?;
+    let nonce = decode("nonce")?;
+    let ad = decode("ad")?;
+
+    let result = match mode.as_str() {
+        "encrypt" => {
+            let (tag, l, h) = gcm_stream::encrypt_file(algorithm, key, &nonce, &ad, tag_length.unwrap_or(16), input, output)?;
+            json!({
+                "tag": de_encode_base64::encode(tag),
+                "L": de_encode_base64::encode(l),
+                "H": de_encode_base64::encode(h)
+            })
+        }
+        "decrypt" => {
+            let tag = decode("tag")?;
+            // The tag length is implied by the tag, a given one has to agree with it
+            if tag_length.is_some_and(|length| length != tag.len()) {
+                return Err(format!("Tag has {} bytes, but --tag-length is {}", tag.len(), tag_length.unwrap()));
+            }
 
-}
\ No newline at end of file
+            let authentic = gcm_stream::decrypt_file(algorithm, key, &nonce, &ad, &tag, input, output)?;
+            json!({"authentic": authentic})
+        }
+        _ => return Err(format!("Unknown mode '{}'", mode))
+    };
+    Ok(result)
+}
@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
+use crate::actions::{de_encode_base64, poly_notation};
 
 #[derive(Deserialize, Debug)]
 pub struct TestCases {
@@ -19,24 +20,78 @@ pub enum TestCase {
     gfmul { semantic: String, a: String, b: String },
     gfdiv { a: String, b: String },
     sea128 { mode: String, key: String, input: String },
-    xex { mode: String, key: String, tweak: String, input: String },
-    gcm_encrypt { algorithm: String, nonce: String, key: String, plaintext: String, ad: String },
-    gcm_decrypt { algorithm: String, nonce: String, key: String, ciphertext: String, ad: String, tag: String },
+    xex { mode: String, key: String, tweak: String, input: String, algorithm: Option<String> },
+    block_encrypt { algorithm: String, mode: String, padding: Option<String>, key: String, iv: Option<String>, plaintext: String },
+    block_decrypt { algorithm: String, mode: String, padding: Option<String>, key: String, iv: Option<String>, ciphertext: String },
+    gcm_encrypt { algorithm: String, nonce: String, key: String, plaintext: String, ad: String, tag_length: Option<usize> },
+    gcm_decrypt { algorithm: String, nonce: String, key: String, ciphertext: String, ad: String, tag: String, tag_length: Option<usize>, diagnostic: Option<bool> },
+    gcm_siv_encrypt { algorithm: String, nonce: String, key: String, plaintext: String, ad: String },
+    gcm_siv_decrypt { algorithm: String, nonce: String, key: String, ciphertext: String, ad: String, tag: String },
+    chacha20poly1305_encrypt { nonce: String, key: String, plaintext: String, ad: String },
+    chacha20poly1305_decrypt { nonce: String, key: String, ciphertext: String, ad: String, tag: String, diagnostic: Option<bool> },
+    ccm_encrypt { algorithm: String, nonce: String, key: String, plaintext: String, ad: String, tag_length: Option<usize> },
+    ccm_decrypt { algorithm: String, nonce: String, key: String, ciphertext: String, ad: String, tag: String, tag_length: Option<usize> },
+    eax_encrypt { algorithm: String, nonce: String, key: String, plaintext: String, ad: String, tag_length: Option<usize> },
+    eax_decrypt { algorithm: String, nonce: String, key: String, ciphertext: String, ad: String, tag: String, tag_length: Option<usize> },
+    gmac { algorithm: String, nonce: String, key: String, data: String, tag_length: Option<usize>, diagnostic: Option<bool> },
+    gmac_verify { algorithm: String, nonce: String, key: String, data: String, tag: String, tag_length: Option<usize>, diagnostic: Option<bool> },
     padding_oracle { hostname: String, port: u32, iv: String, ciphertext: String },
-    gfpoly_add { A: Vec<String>, B: Vec<String> },
-    gfpoly_mul { A: Vec<String>, B: Vec<String> },
-    gfpoly_divmod { A: Vec<String>, B: Vec<String> },
-    gfpoly_pow { A: Vec<String>, k: u128 },
-    gfpoly_powmod { A: Vec<String>, M: Vec<String>, k: u128 },
-    gfpoly_sort { polys: Vec<Vec<String>> },
-    gfpoly_make_monic { A: Vec<String> },
-    gfpoly_sqrt { Q: Vec<String> },
-    gfpoly_diff { F: Vec<String> },
-    gfpoly_gcd { A: Vec<String>, B: Vec<String> },
-    gfpoly_factor_sff { F: Vec<String> },
-    gfpoly_factor_ddf { F: Vec<String> },
-    gfpoly_factor_edf { F: Vec<String>, d: u128 },
-    gcm_crack { nonce: String, m1: Message, m2: Message, m3: Message, forgery: Forgery}
+    gfpoly_add { A: Polynomial, B: Polynomial, format: Option<String> },
+    gfpoly_mul { A: Polynomial, B: Polynomial, format: Option<String> },
+    gfpoly_divmod { A: Polynomial, B: Polynomial, format: Option<String> },
+    gfpoly_pow { A: Polynomial, k: u128, format: Option<String> },
+    gfpoly_powmod { A: Polynomial, M: Polynomial, k: u128, format: Option<String> },
+    gfpoly_sort { polys: Vec<Polynomial>, format: Option<String> },
+    gfpoly_make_monic { A: Polynomial, format: Option<String> },
+    gfpoly_sqrt { Q: Polynomial, format: Option<String> },
+    gfpoly_diff { F: Polynomial, format: Option<String> },
+    gfpoly_gcd { A: Polynomial, B: Polynomial, format: Option<String> },
+    gfpoly_xgcd { A: Polynomial, B: Polynomial, format: Option<String> },
+    gfpoly_compose { F: Polynomial, G: Polynomial, format: Option<String> },
+    gfpoly_compose_mod { F: Polynomial, G: Polynomial, M: Polynomial, format: Option<String> },
+    gfpoly_invmod { A: Polynomial, M: Polynomial, format: Option<String> },
+    gfpoly_crt { residues: Vec<Polynomial>, moduli: Vec<Polynomial>, format: Option<String> },
+    gfpoly_resultant { A: Polynomial, B: Polynomial, format: Option<String> },
+    gfpoly_discriminant { F: Polynomial, format: Option<String> },
+    gfpoly_factor_sff { F: Polynomial, format: Option<String> },
+    gfpoly_factor_ddf { F: Polynomial, algorithm: Option<String>, format: Option<String> },
+    gfpoly_factor_edf { F: Polynomial, d: Option<u128>, algorithm: Option<String>, seed: Option<u64>, format: Option<String> },
+    gfpoly_factor { F: Polynomial, algorithm: Option<String>, format: Option<String> },
+    gfpoly_is_irreducible { F: Polynomial },
+    gfpoly_random_irreducible { degree: usize, seed: Option<u64>, format: Option<String> },
+    gfpoly_roots { F: Polynomial, multiplicities: Option<bool>, format: Option<String> },
+    berlekamp_massey { sequence: Option<Vec<String>>, bits: Option<Vec<u8>>, format: Option<String> },
+    gfmatrix_rref { A: Vec<Vec<String>> },
+    gfmatrix_rank { A: Vec<Vec<String>> },
+    gfmatrix_det { A: Vec<Vec<String>> },
+    gfmatrix_inverse { A: Vec<Vec<String>> },
+    gfmatrix_solve { A: Vec<Vec<String>>, b: Vec<String> },
+    gfmatrix_nullspace { A: Vec<Vec<String>> },
+    bitmatrix_build { semantic: String, map: String, c: Option<String>, k: Option<u32> },
+    bitmatrix_apply { M: Vec<String>, x: String },
+    bitmatrix_kernel { M: Vec<String>, columns: Option<usize> },
+    gf2poly_factor { F: Vec<u32> },
+    gf2poly_is_irreducible { F: Vec<u32> },
+    gf2poly_is_primitive { F: Vec<u32> },
+    gcm_crack { nonce: String, m1: Message, m2: Message, m3: Message, forgery: Forgery, seed: Option<u64> }
+}
+
+// A polynomial is either an array of base64 coefficients or a string in the text notation
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum Polynomial {
+    Blocks(Vec<String>),
+    Text(String)
+}
+
+impl Polynomial {
+    pub fn decode(self) -> Result<Vec<Vec<u8>>, String> {
+        match self {
+            Polynomial::Blocks(blocks) => Ok(de_encode_base64::decode_vectors(blocks)),
+            Polynomial::Text(text) => poly_notation::text_to_poly(&text)
+                .map_err(|e| format!("Failed to parse polynomial '{}': {}", text, e))
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]